@@ -0,0 +1,396 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Read, Write};
+use serde_json::{json, Value};
+use crate::error::PenyuError;
+use crate::model::graph::Graph;
+use crate::model::iri::Iri;
+use crate::model::node::{Entity, Node};
+use crate::read;
+use crate::vocabs::{obo, obo_in_owl, owl, rdf, rdfs};
+
+/// A class of an ontology, with its label, if it has one.
+#[derive(Clone)]
+pub struct ClassEntry {
+    pub iri: Iri,
+    pub label: Option<String>,
+}
+
+/// Values of one property of a class that only the old or only the new release has.
+#[derive(Clone, Default)]
+pub struct ValueChange {
+    pub removed: Vec<String>,
+    pub added: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Obsoletion {
+    Obsoleted,
+    Unobsoleted,
+}
+
+/// The changes to a class present in both releases. Parents are given as IRIs.
+#[derive(Clone)]
+pub struct ClassChange {
+    pub class: ClassEntry,
+    pub labels: ValueChange,
+    pub definitions: ValueChange,
+    pub synonyms: ValueChange,
+    pub parents: ValueChange,
+    pub obsoletion: Option<Obsoletion>,
+}
+
+/// The differences between two releases of an ontology, classes sorted by IRI.
+#[derive(Clone, Default)]
+pub struct OntologyDiff {
+    pub added_classes: Vec<ClassEntry>,
+    pub removed_classes: Vec<ClassEntry>,
+    pub changed_classes: Vec<ClassChange>,
+}
+
+/// Labels, definitions, exact synonyms, named parents and obsoletion of a class.
+struct ClassData {
+    labels: BTreeSet<String>,
+    definitions: BTreeSet<String>,
+    synonyms: BTreeSet<String>,
+    parents: BTreeSet<String>,
+    is_obsolete: bool,
+}
+
+impl ValueChange {
+    fn new(old: &BTreeSet<String>, new: &BTreeSet<String>) -> ValueChange {
+        ValueChange {
+            removed: old.difference(new).cloned().collect(),
+            added: new.difference(old).cloned().collect(),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty()
+    }
+    fn to_json(&self) -> Value {
+        json!({ "removed": self.removed, "added": self.added })
+    }
+}
+
+impl ClassEntry {
+    fn new<G: Graph>(graph: &G, iri: &Iri) -> ClassEntry {
+        let label =
+            graph.best_label(&Entity::Iri(iri.clone()), &["en"]).map(|label| label.string);
+        ClassEntry { iri: iri.clone(), label }
+    }
+    fn to_json(&self) -> Value {
+        json!({ "iri": self.iri.to_string(), "label": self.label })
+    }
+    fn to_markdown(&self) -> String {
+        match &self.label {
+            Some(label) => { format!("{} (`{}`)", markdown_text(label), self.iri) }
+            None => { format!("`{}`", self.iri) }
+        }
+    }
+}
+
+impl ClassChange {
+    fn to_json(&self) -> Value {
+        let obsoletion =
+            self.obsoletion.map(|obsoletion| match obsoletion {
+                Obsoletion::Obsoleted => { "obsoleted" }
+                Obsoletion::Unobsoleted => { "unobsoleted" }
+            });
+        json!({
+            "iri": self.class.iri.to_string(),
+            "label": self.class.label,
+            "labels": self.labels.to_json(),
+            "definitions": self.definitions.to_json(),
+            "synonyms": self.synonyms.to_json(),
+            "parents": self.parents.to_json(),
+            "obsoletion": obsoletion,
+        })
+    }
+}
+
+impl OntologyDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_classes.is_empty() && self.removed_classes.is_empty()
+            && self.changed_classes.is_empty()
+    }
+    /// The classes that the new release marks as obsolete and the old release does not.
+    pub fn obsoleted_classes(&self) -> impl Iterator<Item=&ClassEntry> {
+        self.changed_classes.iter()
+            .filter(|change| change.obsoletion == Some(Obsoletion::Obsoleted))
+            .map(|change| &change.class)
+    }
+    pub fn to_json(&self) -> Value {
+        let entries = |classes: &[ClassEntry]| -> Vec<Value> {
+            classes.iter().map(ClassEntry::to_json).collect()
+        };
+        let changed: Vec<Value> = self.changed_classes.iter().map(ClassChange::to_json).collect();
+        json!({
+            "added_classes": entries(&self.added_classes),
+            "removed_classes": entries(&self.removed_classes),
+            "changed_classes": changed,
+        })
+    }
+    /// Writes the diff as a JSON report.
+    pub fn write_json<W: Write>(&self, writer: &mut W) -> Result<(), PenyuError> {
+        serde_json::to_writer_pretty(&mut *writer, &self.to_json())?;
+        writeln!(writer)?;
+        Ok(())
+    }
+    /// Writes the diff as a Markdown changelog.
+    pub fn write_markdown<W: Write>(&self, writer: &mut W) -> Result<(), PenyuError> {
+        let obsoleted: Vec<&ClassEntry> = self.obsoleted_classes().collect();
+        writeln!(writer, "# Ontology changes")?;
+        writeln!(writer)?;
+        writeln!(writer, "- Added classes: {}", self.added_classes.len())?;
+        writeln!(writer, "- Removed classes: {}", self.removed_classes.len())?;
+        writeln!(writer, "- Obsoleted classes: {}", obsoleted.len())?;
+        writeln!(writer, "- Changed classes: {}", self.changed_classes.len())?;
+        write_class_list(writer, "Added classes", self.added_classes.iter())?;
+        write_class_list(writer, "Removed classes", self.removed_classes.iter())?;
+        write_class_list(writer, "Obsoleted classes", obsoleted.into_iter())?;
+        if !self.changed_classes.is_empty() {
+            writeln!(writer)?;
+            writeln!(writer, "## Changed classes")?;
+        }
+        for change in &self.changed_classes {
+            writeln!(writer)?;
+            writeln!(writer, "### {}", change.class.to_markdown())?;
+            writeln!(writer)?;
+            match change.obsoletion {
+                Some(Obsoletion::Obsoleted) => { writeln!(writer, "- Obsoleted")?; }
+                Some(Obsoletion::Unobsoleted) => { writeln!(writer, "- No longer obsolete")?; }
+                None => {}
+            }
+            write_value_change(writer, "label", &change.labels, false)?;
+            write_value_change(writer, "definition", &change.definitions, false)?;
+            write_value_change(writer, "exact synonym", &change.synonyms, false)?;
+            write_value_change(writer, "parent", &change.parents, true)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_class_list<'a, W: Write>(writer: &mut W, title: &str,
+                                  classes: impl Iterator<Item=&'a ClassEntry>)
+                                  -> Result<(), PenyuError> {
+    let mut classes = classes.peekable();
+    if classes.peek().is_none() {
+        return Ok(());
+    }
+    writeln!(writer)?;
+    writeln!(writer, "## {}", title)?;
+    writeln!(writer)?;
+    for class in classes {
+        writeln!(writer, "- {}", class.to_markdown())?;
+    }
+    Ok(())
+}
+
+fn write_value_change<W: Write>(writer: &mut W, name: &str, change: &ValueChange,
+                                is_iri: bool) -> Result<(), PenyuError> {
+    let format_value =
+        |value: &String| {
+            if is_iri { format!("`{}`", value) } else { format!("\"{}\"", markdown_text(value)) }
+        };
+    for value in &change.removed {
+        writeln!(writer, "- Removed {}: {}", name, format_value(value))?;
+    }
+    for value in &change.added {
+        writeln!(writer, "- Added {}: {}", name, format_value(value))?;
+    }
+    Ok(())
+}
+
+/// The text on a single line, with characters that Markdown would interpret escaped.
+fn markdown_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\n' | '\r' => { escaped.push(' '); }
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => { escaped.push(c); }
+        }
+    }
+    escaped
+}
+
+/// Compares two releases of an ontology, read as RDF/XML.
+pub fn diff_xml<R1: Read, R2: Read>(old: &mut R1, new: &mut R2)
+                                    -> Result<OntologyDiff, PenyuError> {
+    let old = read::xml::read(old)?;
+    let new = read::xml::read(new)?;
+    Ok(diff(&old, &new))
+}
+
+/// Compares two releases of an ontology. Classes are the IRIs typed as `owl:Class`, and only
+/// named parents count as parents.
+pub fn diff<G1: Graph, G2: Graph>(old: &G1, new: &G2) -> OntologyDiff {
+    let old_classes = classes(old);
+    let new_classes = classes(new);
+    let mut diff = OntologyDiff::default();
+    for (iri, old_data) in &old_classes {
+        match new_classes.get(iri) {
+            None => { diff.removed_classes.push(ClassEntry::new(old, iri)); }
+            Some(new_data) => {
+                let obsoletion =
+                    match (old_data.is_obsolete, new_data.is_obsolete) {
+                        (false, true) => { Some(Obsoletion::Obsoleted) }
+                        (true, false) => { Some(Obsoletion::Unobsoleted) }
+                        _ => { None }
+                    };
+                let change = ClassChange {
+                    class: ClassEntry::new(new, iri),
+                    labels: ValueChange::new(&old_data.labels, &new_data.labels),
+                    definitions: ValueChange::new(&old_data.definitions, &new_data.definitions),
+                    synonyms: ValueChange::new(&old_data.synonyms, &new_data.synonyms),
+                    parents: ValueChange::new(&old_data.parents, &new_data.parents),
+                    obsoletion,
+                };
+                let is_changed =
+                    change.obsoletion.is_some() || !change.labels.is_empty()
+                        || !change.definitions.is_empty() || !change.synonyms.is_empty()
+                        || !change.parents.is_empty();
+                if is_changed {
+                    diff.changed_classes.push(change);
+                }
+            }
+        }
+    }
+    for iri in new_classes.keys().filter(|iri| !old_classes.contains_key(*iri)) {
+        diff.added_classes.push(ClassEntry::new(new, iri));
+    }
+    diff
+}
+
+fn classes<G: Graph>(graph: &G) -> BTreeMap<Iri, ClassData> {
+    let class_type = Node::from(owl::CLASS);
+    let mut classes: BTreeMap<Iri, ClassData> = BTreeMap::new();
+    for triple in graph.triples_matching(None, Some(rdf::TYPE), Some(&class_type)) {
+        if let Entity::Iri(iri) = &triple.subject {
+            classes.insert(iri.clone(), class_data(graph, &triple.subject));
+        }
+    }
+    classes
+}
+
+fn class_data<G: Graph>(graph: &G, class: &Entity) -> ClassData {
+    let literals = |predicate: &Iri| -> BTreeSet<String> {
+        graph.triples_matching(Some(class), Some(predicate), None)
+            .filter_map(|triple| match triple.object {
+                Node::Literal(literal) => { Some(literal.string) }
+                Node::Entity(_) => { None }
+            }).collect()
+    };
+    let parents =
+        graph.triples_matching(Some(class), Some(rdfs::SUB_CLASS_OF), None)
+            .filter_map(|triple| match triple.object {
+                Node::Entity(Entity::Iri(parent)) => { Some(parent.to_string()) }
+                _ => { None }
+            }).collect();
+    let is_obsolete =
+        literals(owl::DEPRECATED).iter().any(|value| value == "true" || value == "1");
+    ClassData {
+        labels: literals(rdfs::LABEL),
+        definitions: literals(obo::DEFINITION),
+        synonyms: literals(obo_in_owl::HAS_EXACT_SYNONYM),
+        parents,
+        is_obsolete,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    const OLD: &str = r#"<?xml version="1.0"?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+         xmlns:rdfs="http://www.w3.org/2000/01/rdf-schema#"
+         xmlns:owl="http://www.w3.org/2002/07/owl#"
+         xmlns:obo="http://purl.obolibrary.org/obo/"
+         xmlns:oboInOwl="http://www.geneontology.org/formats/oboInOwl#">
+    <owl:Class rdf:about="http://purl.obolibrary.org/obo/MONDO_0000001">
+        <rdfs:label>disease</rdfs:label>
+    </owl:Class>
+    <owl:Class rdf:about="http://purl.obolibrary.org/obo/MONDO_0005148">
+        <rdfs:label>type 2 diabetes</rdfs:label>
+        <rdfs:subClassOf rdf:resource="http://purl.obolibrary.org/obo/MONDO_0000001"/>
+        <oboInOwl:hasExactSynonym>NIDDM</oboInOwl:hasExactSynonym>
+    </owl:Class>
+    <owl:Class rdf:about="http://purl.obolibrary.org/obo/MONDO_0000002">
+        <rdfs:label>old disease</rdfs:label>
+    </owl:Class>
+    <owl:Class rdf:about="http://purl.obolibrary.org/obo/MONDO_0000003">
+        <rdfs:label>gone disease</rdfs:label>
+    </owl:Class>
+</rdf:RDF>
+"#;
+
+    const NEW: &str = r#"<?xml version="1.0"?>
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+         xmlns:rdfs="http://www.w3.org/2000/01/rdf-schema#"
+         xmlns:owl="http://www.w3.org/2002/07/owl#"
+         xmlns:obo="http://purl.obolibrary.org/obo/"
+         xmlns:oboInOwl="http://www.geneontology.org/formats/oboInOwl#">
+    <owl:Class rdf:about="http://purl.obolibrary.org/obo/MONDO_0000001">
+        <rdfs:label>disease</rdfs:label>
+    </owl:Class>
+    <owl:Class rdf:about="http://purl.obolibrary.org/obo/MONDO_0005148">
+        <rdfs:label>type 2 diabetes mellitus</rdfs:label>
+        <obo:IAO_0000115>A diabetes that is not insulin dependent.</obo:IAO_0000115>
+        <rdfs:subClassOf rdf:resource="http://purl.obolibrary.org/obo/MONDO_0000004"/>
+        <oboInOwl:hasExactSynonym>NIDDM</oboInOwl:hasExactSynonym>
+        <oboInOwl:hasExactSynonym>T2DM</oboInOwl:hasExactSynonym>
+    </owl:Class>
+    <owl:Class rdf:about="http://purl.obolibrary.org/obo/MONDO_0000002">
+        <rdfs:label>obsolete old disease</rdfs:label>
+        <owl:deprecated rdf:datatype="http://www.w3.org/2001/XMLSchema#boolean"
+            >true</owl:deprecated>
+    </owl:Class>
+    <owl:Class rdf:about="http://purl.obolibrary.org/obo/MONDO_0000004">
+        <rdfs:label>diabetes</rdfs:label>
+        <rdfs:subClassOf rdf:resource="http://purl.obolibrary.org/obo/MONDO_0000001"/>
+    </owl:Class>
+</rdf:RDF>
+"#;
+
+    #[test]
+    fn diffs_releases() {
+        let diff = super::diff_xml(&mut OLD.as_bytes(), &mut NEW.as_bytes()).unwrap();
+        assert_eq!(diff.added_classes.len(), 1);
+        assert_eq!(diff.added_classes[0].label.as_deref(), Some("diabetes"));
+        assert_eq!(diff.removed_classes.len(), 1);
+        assert_eq!(diff.removed_classes[0].label.as_deref(), Some("gone disease"));
+        assert_eq!(diff.changed_classes.len(), 2);
+        assert_eq!(diff.obsoleted_classes().count(), 1);
+        let t2d = &diff.changed_classes[1];
+        assert_eq!(t2d.labels.removed, vec!["type 2 diabetes".to_string()]);
+        assert_eq!(t2d.labels.added, vec!["type 2 diabetes mellitus".to_string()]);
+        assert_eq!(t2d.definitions.added.len(), 1);
+        assert_eq!(t2d.synonyms.added, vec!["T2DM".to_string()]);
+        assert!(t2d.synonyms.removed.is_empty());
+        assert_eq!(t2d.parents.removed,
+                   vec!["http://purl.obolibrary.org/obo/MONDO_0000001".to_string()]);
+        assert!(t2d.obsoletion.is_none());
+        assert!(super::diff_xml(&mut OLD.as_bytes(), &mut OLD.as_bytes()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn writes_reports() {
+        let diff = super::diff_xml(&mut OLD.as_bytes(), &mut NEW.as_bytes()).unwrap();
+        let mut bytes: Vec<u8> = Vec::new();
+        diff.write_markdown(&mut bytes).unwrap();
+        let markdown = String::from_utf8(bytes).unwrap();
+        assert!(markdown.contains("- Added classes: 1\n"), "{}", markdown);
+        assert!(markdown.contains(
+            "## Obsoleted classes\n\n- obsolete old disease \
+            (`http://purl.obolibrary.org/obo/MONDO_0000002`)\n"
+        ), "{}", markdown);
+        assert!(markdown.contains("- Added exact synonym: \"T2DM\"\n"), "{}", markdown);
+        let mut bytes: Vec<u8> = Vec::new();
+        diff.write_json(&mut bytes).unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(report["removed_classes"][0]["label"], "gone disease");
+        assert_eq!(report["changed_classes"][0]["obsoletion"], "obsoleted");
+        assert_eq!(report["changed_classes"][1]["synonyms"]["added"][0], "T2DM");
+    }
+}
@@ -56,4 +56,10 @@ impl From<xml::reader::Error> for PenyuError {
     fn from(xml_reader_error: xml::reader::Error) -> Self {
         PenyuError::new("XML reader error".to_string(), Some(Box::new(xml_reader_error)))
     }
+}
+
+impl From<serde_json::Error> for PenyuError {
+    fn from(json_error: serde_json::Error) -> Self {
+        PenyuError::new("JSON error".to_string(), Some(Box::new(json_error)))
+    }
 }
\ No newline at end of file
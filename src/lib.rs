@@ -4,3 +4,4 @@ pub mod vocabs;
 pub mod syntax;
 pub mod write;
 pub mod read;
+pub mod diff;
@@ -3,4 +3,9 @@ pub mod iri;
 pub mod literal;
 pub mod triple;
 pub mod graph;
-
+pub mod value;
+pub mod lang_tag;
+pub mod quad;
+pub mod dataset;
+pub mod isomorphism;
+pub mod set_ops;
\ No newline at end of file
@@ -0,0 +1,233 @@
+use crate::model::graph::{Graph, MemoryGraph, MutableGraph};
+use crate::model::iri::Iri;
+use crate::model::node::{Entity, Node};
+use crate::model::quad::Quad;
+use crate::model::triple::Triple;
+use std::collections::BTreeMap;
+
+/// A collection of graphs: one default graph and any number of named graphs.
+pub trait Dataset {
+    fn base_ns(&self) -> &Option<Iri>;
+    fn prefixes(&self) -> &BTreeMap<String, Iri>;
+    fn quads(&self) -> impl Iterator<Item=Quad>;
+    /// Names of all non-empty named graphs.
+    fn graph_names(&self) -> impl Iterator<Item=&Entity>;
+    fn is_empty(&self) -> bool;
+    fn len(&self) -> usize;
+    /// All quads matching the pattern, where `None` matches any term. For the graph name,
+    /// `Some(None)` matches only the default graph.
+    fn quads_matching(&self, subject: Option<&Entity>, predicate: Option<&Iri>,
+                      object: Option<&Node>, graph_name: Option<Option<&Entity>>)
+                      -> impl Iterator<Item=Quad> {
+        self.quads().filter(move |quad| {
+            subject.is_none_or(|subject| *subject == quad.subject)
+                && predicate.is_none_or(|predicate| *predicate == quad.predicate)
+                && object.is_none_or(|object| *object == quad.object)
+                && graph_name.is_none_or(|graph_name| graph_name == quad.graph_name.as_ref())
+        })
+    }
+    fn contains(&self, quad: &Quad) -> bool {
+        self.quads_matching(Some(&quad.subject), Some(&quad.predicate), Some(&quad.object),
+                            Some(quad.graph_name.as_ref())).next().is_some()
+    }
+}
+
+pub trait MutableDataset: Dataset {
+    /// Returns whether the quad was not already present.
+    fn insert(&mut self, quad: Quad) -> bool;
+    /// Returns whether the quad was present.
+    fn remove(&mut self, quad: &Quad) -> bool;
+    fn clear(&mut self);
+    fn extend<I: IntoIterator<Item=Quad>>(&mut self, quads: I) {
+        for quad in quads {
+            self.insert(quad);
+        }
+    }
+}
+
+pub struct MemoryDataset {
+    base_ns: Option<Iri>,
+    prefixes: BTreeMap<String, Iri>,
+    default_graph: MemoryGraph,
+    named_graphs: BTreeMap<Entity, MemoryGraph>,
+}
+
+impl MemoryDataset {
+    pub fn new() -> MemoryDataset {
+        MemoryDataset {
+            base_ns: None,
+            prefixes: BTreeMap::new(),
+            default_graph: MemoryGraph::new(),
+            named_graphs: BTreeMap::new(),
+        }
+    }
+    pub fn set_base_ns(&mut self, base_ns: Iri) {
+        self.base_ns = Some(base_ns);
+    }
+    pub fn add_prefix(&mut self, prefix: String, iri: Iri) {
+        self.prefixes.insert(prefix, iri);
+    }
+    pub fn add_quad(&mut self, quad: Quad) {
+        self.insert(quad);
+    }
+    pub fn add<S, P, O>(&mut self, subject: S, predicate: P, object: O, graph_name: Option<Entity>)
+    where
+        S: Into<Entity>,
+        P: Into<Iri>,
+        O: Into<Node>,
+    {
+        self.insert(Quad::create(subject, predicate, object, graph_name));
+    }
+    pub fn default_graph(&self) -> &MemoryGraph {
+        &self.default_graph
+    }
+    pub fn named_graph(&self, graph_name: &Entity) -> Option<&MemoryGraph> {
+        self.named_graphs.get(graph_name)
+    }
+    /// The graph with the name, or the default graph for `None`.
+    pub fn graph(&self, graph_name: Option<&Entity>) -> Option<&MemoryGraph> {
+        match graph_name {
+            None => { Some(&self.default_graph) }
+            Some(graph_name) => { self.named_graphs.get(graph_name) }
+        }
+    }
+    /// Adds the triples of the graph to the named graph, or the default graph for `None`,
+    /// and the graph's prefixes to the dataset's prefixes, where not already bound.
+    pub fn add_graph<G: Graph>(&mut self, graph_name: Option<Entity>, graph: &G) {
+        for (prefix, iri) in graph.prefixes() {
+            self.prefixes.entry(prefix.clone()).or_insert_with(|| iri.clone());
+        }
+        let target =
+            match graph_name {
+                None => { &mut self.default_graph }
+                Some(graph_name) => { self.named_graphs.entry(graph_name).or_default() }
+            };
+        target.extend(graph.triples());
+        if target.is_empty() {
+            self.named_graphs.retain(|_, graph| !graph.is_empty());
+        }
+    }
+    /// The default graph, with the dataset's base and prefixes.
+    pub fn into_default_graph(self) -> MemoryGraph {
+        let mut graph = self.default_graph;
+        if let Some(base_ns) = self.base_ns {
+            graph.set_base_ns(base_ns);
+        }
+        for (prefix, iri) in self.prefixes {
+            graph.add_prefix(prefix, iri);
+        }
+        graph
+    }
+    fn graphs(&self) -> impl Iterator<Item=(Option<&Entity>, &MemoryGraph)> {
+        std::iter::once((None, &self.default_graph))
+            .chain(self.named_graphs.iter().map(|(name, graph)| (Some(name), graph)))
+    }
+}
+
+impl Default for MemoryDataset {
+    fn default() -> Self {
+        MemoryDataset::new()
+    }
+}
+
+impl Dataset for MemoryDataset {
+    fn base_ns(&self) -> &Option<Iri> { &self.base_ns }
+    fn prefixes(&self) -> &BTreeMap<String, Iri> { &self.prefixes }
+    fn quads(&self) -> impl Iterator<Item=Quad> {
+        self.graphs().flat_map(|(graph_name, graph)| {
+            graph.triples().map(move |triple| Quad::from_triple(triple, graph_name.cloned()))
+        })
+    }
+    fn graph_names(&self) -> impl Iterator<Item=&Entity> {
+        self.named_graphs.keys()
+    }
+    fn is_empty(&self) -> bool {
+        self.default_graph.is_empty() && self.named_graphs.is_empty()
+    }
+    fn len(&self) -> usize {
+        self.graphs().map(|(_, graph)| graph.len()).sum()
+    }
+    fn quads_matching(&self, subject: Option<&Entity>, predicate: Option<&Iri>,
+                      object: Option<&Node>, graph_name: Option<Option<&Entity>>)
+                      -> impl Iterator<Item=Quad> {
+        self.graphs()
+            .filter(move |(name, _)| graph_name.is_none_or(|graph_name| graph_name == *name))
+            .flat_map(move |(name, graph)| {
+                graph.triples_matching(subject, predicate, object)
+                    .map(move |triple| Quad::from_triple(triple, name.cloned()))
+            })
+    }
+}
+
+impl MutableDataset for MemoryDataset {
+    fn insert(&mut self, quad: Quad) -> bool {
+        let triple = Triple::new(quad.subject, quad.predicate, quad.object);
+        match quad.graph_name {
+            None => { self.default_graph.insert(triple) }
+            Some(graph_name) => { self.named_graphs.entry(graph_name).or_default().insert(triple) }
+        }
+    }
+    fn remove(&mut self, quad: &Quad) -> bool {
+        let triple = quad.triple();
+        match &quad.graph_name {
+            None => { self.default_graph.remove(&triple) }
+            Some(graph_name) => {
+                let Some(graph) = self.named_graphs.get_mut(graph_name) else { return false; };
+                let was_present = graph.remove(&triple);
+                if graph.is_empty() {
+                    self.named_graphs.remove(graph_name);
+                }
+                was_present
+            }
+        }
+    }
+    fn clear(&mut self) {
+        self.default_graph.clear();
+        self.named_graphs.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::dataset::{Dataset, MemoryDataset, MutableDataset};
+    use crate::model::graph::{Graph, MemoryGraph};
+    use crate::model::iri::Iri;
+    use crate::model::literal::Literal;
+    use crate::model::node::Entity;
+    use crate::model::quad::Quad;
+    use crate::vocabs::{obo, rdfs};
+
+    #[test]
+    fn keeps_graphs_apart() {
+        let mut dataset = MemoryDataset::new();
+        let liver = obo::Ontology::UBERON.create_iri(2107);
+        let uberon = Entity::from(Iri::from("http://purl.obolibrary.org/obo/uberon.owl"));
+        let fma = Entity::from(Iri::from("http://purl.obolibrary.org/obo/fma.owl"));
+        dataset.add(&liver, rdfs::LABEL, Literal::from("liver".to_string()), None);
+        dataset.add(&liver, rdfs::LABEL, Literal::from("liver".to_string()),
+                    Some(uberon.clone()));
+        let mut graph = MemoryGraph::new();
+        graph.add_prefix(obo::PREFIX.to_string(), obo::NAMESPACE.clone());
+        graph.add(&liver, rdfs::LABEL, Literal::from("Liver".to_string()));
+        dataset.add_graph(Some(fma.clone()), &graph);
+        assert_eq!(dataset.len(), 3);
+        assert_eq!(dataset.graph_names().count(), 2);
+        assert_eq!(dataset.prefixes().len(), 1);
+        assert_eq!(dataset.quads_matching(None, None, None, Some(None)).count(), 1);
+        assert_eq!(dataset.quads_matching(Some(&Entity::from(&liver)), None, None, None).count(),
+                   3);
+        let quad =
+            Quad::create(&liver, rdfs::LABEL, Literal::from("Liver".to_string()),
+                         Some(fma.clone()));
+        assert!(dataset.contains(&quad));
+        assert!(dataset.named_graph(&fma).is_some_and(|graph| graph.len() == 1));
+        assert!(dataset.remove(&quad));
+        assert!(dataset.named_graph(&fma).is_none());
+        assert_eq!(dataset.graph_names().count(), 1);
+        assert!(dataset.insert(Quad::from_triple(quad.into_triple(), Some(fma))));
+        assert!(!dataset.insert(Quad::create(&liver, rdfs::LABEL,
+                                             Literal::from("liver".to_string()), Some(uberon))));
+        dataset.clear();
+        assert!(dataset.is_empty());
+    }
+}
@@ -1,7 +1,11 @@
 use crate::model::iri::Iri;
+use crate::model::lang_tag;
+use crate::model::literal::{Literal, LiteralTag};
 use crate::model::node::{Entity, Node};
 use crate::model::triple::Triple;
-use std::collections::BTreeMap;
+use crate::vocabs;
+use std::collections::{BTreeMap, BTreeSet};
+use std::iter;
 
 pub trait Graph {
     fn base_ns(&self) -> &Option<Iri>;
@@ -9,20 +13,101 @@ pub trait Graph {
     fn triples(&self) -> impl Iterator<Item=Triple>;
     fn is_empty(&self) -> bool;
     fn len(&self) -> usize;
+    /// All triples matching the pattern, where `None` matches any term.
+    fn triples_matching(&self, subject: Option<&Entity>, predicate: Option<&Iri>,
+                        object: Option<&Node>) -> impl Iterator<Item=Triple> {
+        self.triples().filter(move |triple| {
+            subject.is_none_or(|subject| *subject == triple.subject)
+                && predicate.is_none_or(|predicate| *predicate == triple.predicate)
+                && object.is_none_or(|object| *object == triple.object)
+        })
+    }
+    fn contains(&self, triple: &Triple) -> bool {
+        self.triples_matching(Some(&triple.subject), Some(&triple.predicate),
+                              Some(&triple.object)).next().is_some()
+    }
+    /// The literal object of the subject and predicate that best fits the language
+    /// preferences: for each language range in order, a literal with exactly that tag, else
+    /// one whose tag the range matches. Failing that, a literal without language tag, else
+    /// any literal.
+    fn best_literal(&self, subject: &Entity, predicate: &Iri, languages: &[&str])
+                    -> Option<Literal> {
+        let literals: Vec<Literal> =
+            self.triples_matching(Some(subject), Some(predicate), None)
+                .filter_map(|triple| match triple.object {
+                    Node::Literal(literal) => { Some(literal) }
+                    Node::Entity(_) => { None }
+                }).collect();
+        let lang_tag = |literal: &Literal| match &literal.literal_tag {
+            LiteralTag::LangTag(lang_tag) => { Some(lang_tag.clone()) }
+            LiteralTag::Type(_) => { None }
+        };
+        let best =
+            languages.iter().find_map(|range| {
+                literals.iter().find(|literal| {
+                    lang_tag(literal).is_some_and(|tag| tag.eq_ignore_ascii_case(range))
+                }).or_else(|| literals.iter().find(|literal| {
+                    lang_tag(literal).is_some_and(|tag| lang_tag::matches(&tag, range))
+                }))
+            }).or_else(|| literals.iter().find(|literal| lang_tag(literal).is_none()))
+                .or(literals.first());
+        best.cloned()
+    }
+    /// The `rdfs:label` of the subject that best fits the language preferences, as in
+    /// `best_literal`.
+    fn best_label(&self, subject: &Entity, languages: &[&str]) -> Option<Literal> {
+        self.best_literal(subject, vocabs::rdfs::LABEL, languages)
+    }
+}
+
+pub trait MutableGraph: Graph {
+    /// Returns whether the triple was not already present.
+    fn insert(&mut self, triple: Triple) -> bool;
+    /// Returns whether the triple was present.
+    fn remove(&mut self, triple: &Triple) -> bool;
+    fn clear(&mut self);
+    /// Removes all triples matching the pattern and returns how many were removed.
+    fn remove_matching(&mut self, subject: Option<&Entity>, predicate: Option<&Iri>,
+                       object: Option<&Node>) -> usize {
+        let triples: Vec<Triple> = self.triples_matching(subject, predicate, object).collect();
+        triples.iter().filter(|triple| self.remove(triple)).count()
+    }
+    /// Replaces all objects of the subject and predicate with the given object.
+    fn replace(&mut self, subject: &Entity, predicate: &Iri, object: Node) {
+        self.remove_matching(Some(subject), Some(predicate), None);
+        self.insert(Triple::create(subject, predicate, object));
+    }
+    fn extend<I: IntoIterator<Item=Triple>>(&mut self, triples: I) {
+        for triple in triples {
+            self.insert(triple);
+        }
+    }
+    fn retain<F: FnMut(&Triple) -> bool>(&mut self, mut keep: F) {
+        let triples: Vec<Triple> = self.triples().filter(|triple| !keep(triple)).collect();
+        for triple in triples {
+            self.remove(&triple);
+        }
+    }
 }
 
 pub struct MemoryGraph {
     base_ns: Option<Iri>,
     prefixes: BTreeMap<String, Iri>,
-    triples: BTreeMap<Entity, BTreeMap<Iri, Node>>,
+    spo: BTreeMap<Entity, BTreeMap<Iri, BTreeSet<Node>>>,
+    pos: BTreeMap<Iri, BTreeMap<Node, BTreeSet<Entity>>>,
+    osp: BTreeMap<Node, BTreeMap<Entity, BTreeSet<Iri>>>,
 }
 
+type TripleIter<'a> = Box<dyn Iterator<Item=Triple> + 'a>;
+
 impl MemoryGraph {
     pub fn new() -> MemoryGraph {
         MemoryGraph {
             base_ns: None,
             prefixes: BTreeMap::new(),
-            triples: BTreeMap::new(),
+            spo: BTreeMap::new(),
+            pos: BTreeMap::new(),
+            osp: BTreeMap::new(),
         }
     }
     pub fn set_base_ns(&mut self, base_ns: Iri) {
@@ -41,13 +126,59 @@ impl MemoryGraph {
         P: Into<Iri>,
         O: Into<Node>,
     {
-        let subject = subject.into();
-        let predicate = predicate.into();
-        let object = object.into();
-        self.triples.entry(subject).or_default().insert(predicate, object);
+        self.insert_terms(subject.into(), predicate.into(), object.into());
+    }
+    fn insert_terms(&mut self, subject: Entity, predicate: Iri, object: Node) -> bool {
+        let is_new =
+            self.spo.entry(subject.clone()).or_default().entry(predicate.clone()).or_default()
+                .insert(object.clone());
+        if is_new {
+            self.pos.entry(predicate.clone()).or_default().entry(object.clone()).or_default()
+                .insert(subject.clone());
+            self.osp.entry(object).or_default().entry(subject).or_default().insert(predicate);
+        }
+        is_new
+    }
+    fn spo_iter<'a>(subject: &'a Entity, predicates: &'a BTreeMap<Iri, BTreeSet<Node>>)
+                    -> TripleIter<'a> {
+        Box::new(predicates.iter().flat_map(move |(predicate, objects)| {
+            objects.iter().map(move |object| {
+                Triple::create(subject, predicate, object)
+            })
+        }))
+    }
+    fn pos_iter<'a>(predicate: &'a Iri, objects: &'a BTreeMap<Node, BTreeSet<Entity>>)
+                    -> TripleIter<'a> {
+        Box::new(objects.iter().flat_map(move |(object, subjects)| {
+            subjects.iter().map(move |subject| {
+                Triple::create(subject, predicate, object)
+            })
+        }))
+    }
+    fn osp_iter<'a>(object: &'a Node, subjects: &'a BTreeMap<Entity, BTreeSet<Iri>>)
+                    -> TripleIter<'a> {
+        Box::new(subjects.iter().flat_map(move |(subject, predicates)| {
+            predicates.iter().map(move |predicate| {
+                Triple::create(subject, predicate, object)
+            })
+        }))
     }
 }
 
+fn remove_from_index<A: Ord, B: Ord, C: Ord>(index: &mut BTreeMap<A, BTreeMap<B, BTreeSet<C>>>,
+                                             a: &A, b: &B, c: &C) -> bool {
+    let Some(bs) = index.get_mut(a) else { return false; };
+    let Some(cs) = bs.get_mut(b) else { return false; };
+    let was_present = cs.remove(c);
+    if cs.is_empty() {
+        bs.remove(b);
+        if bs.is_empty() {
+            index.remove(a);
+        }
+    }
+    was_present
+}
+
 impl Default for MemoryGraph {
     fn default() -> Self { MemoryGraph::new() }
 }
@@ -56,18 +187,229 @@ impl Graph for MemoryGraph {
     fn base_ns(&self) -> &Option<Iri> { &self.base_ns }
     fn prefixes(&self) -> &BTreeMap<String, Iri> { &self.prefixes }
     fn triples(&self) -> impl Iterator<Item=Triple> {
-        self.triples.iter().flat_map(|(subject, predicates)| {
-            predicates.iter().map(move |(predicate, object)| {
-                Triple::create(subject.clone(), predicate.clone(), object.clone())
-            })
+        self.spo.iter().flat_map(|(subject, predicates)| {
+            MemoryGraph::spo_iter(subject, predicates)
         })
     }
 
     fn is_empty(&self) -> bool {
-        self.triples.values().all(|predicates| predicates.is_empty())
+        self.spo.values().all(|predicates| {
+            predicates.values().all(|objects| objects.is_empty())
+        })
     }
 
     fn len(&self) -> usize {
-        self.triples.values().map(|predicates| predicates.len()).sum()
+        self.spo.values().map(|predicates| {
+            predicates.values().map(|objects| objects.len()).sum::<usize>()
+        }).sum()
+    }
+
+    fn triples_matching(&self, subject: Option<&Entity>, predicate: Option<&Iri>,
+                        object: Option<&Node>) -> impl Iterator<Item=Triple> {
+        let iter: TripleIter =
+            match (subject, predicate, object) {
+                (Some(subject), Some(predicate), Some(object)) => {
+                    let is_contained =
+                        self.spo.get(subject).and_then(|predicates| predicates.get(predicate))
+                            .is_some_and(|objects| objects.contains(object));
+                    if is_contained {
+                        Box::new(iter::once(Triple::create(subject, predicate, object)))
+                    } else {
+                        Box::new(iter::empty())
+                    }
+                }
+                (Some(subject), Some(predicate), None) => {
+                    match self.spo.get_key_value(subject).and_then(|(subject, predicates)| {
+                        predicates.get_key_value(predicate)
+                            .map(|(predicate, objects)| (subject, predicate, objects))
+                    }) {
+                        Some((subject, predicate, objects)) => {
+                            Box::new(objects.iter().map(move |object| {
+                                Triple::create(subject, predicate, object)
+                            }))
+                        }
+                        None => { Box::new(iter::empty()) }
+                    }
+                }
+                (Some(subject), None, None) => {
+                    match self.spo.get_key_value(subject) {
+                        Some((subject, predicates)) => {
+                            MemoryGraph::spo_iter(subject, predicates)
+                        }
+                        None => { Box::new(iter::empty()) }
+                    }
+                }
+                (Some(subject), None, Some(object)) => {
+                    match self.osp.get_key_value(object).and_then(|(object, subjects)| {
+                        subjects.get_key_value(subject)
+                            .map(|(subject, predicates)| (subject, predicates, object))
+                    }) {
+                        Some((subject, predicates, object)) => {
+                            Box::new(predicates.iter().map(move |predicate| {
+                                Triple::create(subject, predicate, object)
+                            }))
+                        }
+                        None => { Box::new(iter::empty()) }
+                    }
+                }
+                (None, Some(predicate), Some(object)) => {
+                    match self.pos.get_key_value(predicate).and_then(|(predicate, objects)| {
+                        objects.get_key_value(object)
+                            .map(|(object, subjects)| (predicate, object, subjects))
+                    }) {
+                        Some((predicate, object, subjects)) => {
+                            Box::new(subjects.iter().map(move |subject| {
+                                Triple::create(subject, predicate, object)
+                            }))
+                        }
+                        None => { Box::new(iter::empty()) }
+                    }
+                }
+                (None, Some(predicate), None) => {
+                    match self.pos.get_key_value(predicate) {
+                        Some((predicate, objects)) => {
+                            MemoryGraph::pos_iter(predicate, objects)
+                        }
+                        None => { Box::new(iter::empty()) }
+                    }
+                }
+                (None, None, Some(object)) => {
+                    match self.osp.get_key_value(object) {
+                        Some((object, subjects)) => {
+                            MemoryGraph::osp_iter(object, subjects)
+                        }
+                        None => { Box::new(iter::empty()) }
+                    }
+                }
+                (None, None, None) => { Box::new(self.triples()) }
+            };
+        iter
+    }
+}
+
+impl MutableGraph for MemoryGraph {
+    fn insert(&mut self, triple: Triple) -> bool {
+        self.insert_terms(triple.subject, triple.predicate, triple.object)
+    }
+
+    fn remove(&mut self, triple: &Triple) -> bool {
+        let Triple { subject, predicate, object } = triple;
+        let was_present = remove_from_index(&mut self.spo, subject, predicate, object);
+        if was_present {
+            remove_from_index(&mut self.pos, predicate, object, subject);
+            remove_from_index(&mut self.osp, object, subject, predicate);
+        }
+        was_present
+    }
+
+    fn clear(&mut self) {
+        self.spo.clear();
+        self.pos.clear();
+        self.osp.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::graph::{Graph, MemoryGraph, MutableGraph};
+    use crate::model::literal::{Literal, LiteralTag};
+    use crate::model::iri::Iri;
+    use crate::model::node::{Entity, Node};
+    use crate::model::triple::Triple;
+    use crate::vocabs::{obo, obo_in_owl, rdf, rdfs};
+
+    #[test]
+    fn keeps_multiple_objects() {
+        let mut graph = MemoryGraph::new();
+        let mondo = obo::ns::MONDO.append("0005148".to_string());
+        graph.add(&mondo, rdfs::LABEL, Literal::from("type 2 diabetes mellitus".to_string()));
+        graph.add(&mondo, rdfs::LABEL, Literal::from("T2DM".to_string()));
+        graph.add(&mondo, obo_in_owl::HAS_EXACT_SYNONYM, Literal::from("NIDDM".to_string()));
+        graph.add(&mondo, rdfs::LABEL, Literal::from("T2DM".to_string()));
+        assert_eq!(graph.len(), 3);
+        assert!(!graph.is_empty());
+        let labels: Vec<Node> =
+            graph.triples().filter(|triple| triple.predicate == *rdfs::LABEL)
+                .map(|triple| triple.object).collect();
+        assert!(labels == vec![
+            Node::from(Literal::from("T2DM".to_string())),
+            Node::from(Literal::from("type 2 diabetes mellitus".to_string())),
+        ]);
+    }
+
+    #[test]
+    fn matches_patterns() {
+        let owl_class = Node::from(Iri::from("http://www.w3.org/2002/07/owl#Class"));
+        let liver = Entity::from(obo::ns::UBERON.append("0002107".to_string()));
+        let organ = Entity::from(obo::ns::UBERON.append("0000062".to_string()));
+        let mut graph = MemoryGraph::new();
+        graph.add(&liver, rdf::TYPE, &owl_class);
+        graph.add(&organ, rdf::TYPE, &owl_class);
+        graph.add(&liver, rdfs::SUB_CLASS_OF, &organ);
+        graph.add(&liver, rdfs::LABEL, Literal::from("liver".to_string()));
+        let classes: Vec<Entity> =
+            graph.triples_matching(None, Some(rdf::TYPE), Some(&owl_class))
+                .map(|triple| triple.subject).collect();
+        assert!(classes == vec![organ.clone(), liver.clone()]);
+        let organ_node = Node::from(&organ);
+        let uses: Vec<Triple> =
+            graph.triples_matching(None, None, Some(&organ_node)).collect();
+        assert!(uses == vec![Triple::create(&liver, rdfs::SUB_CLASS_OF, &organ)]);
+        assert_eq!(graph.triples_matching(Some(&liver), None, None).count(), 3);
+        assert_eq!(graph.triples_matching(Some(&liver), None, Some(&owl_class)).count(), 1);
+        assert_eq!(graph.triples_matching(Some(&organ), Some(rdfs::LABEL), None).count(), 0);
+        assert_eq!(graph.triples_matching(None, Some(rdf::TYPE), None).count(), 2);
+        assert_eq!(graph.triples_matching(None, None, None).count(), 4);
+        assert!(graph.contains(&Triple::create(&organ, rdf::TYPE, &owl_class)));
+        assert!(!graph.contains(&Triple::create(&organ, rdfs::SUB_CLASS_OF, &liver)));
+    }
+
+    #[test]
+    fn mutates() {
+        let liver = Entity::from(obo::ns::UBERON.append("0002107".to_string()));
+        let organ = Entity::from(obo::ns::UBERON.append("0000062".to_string()));
+        let mut graph = MemoryGraph::new();
+        graph.extend(vec![
+            Triple::create(&liver, rdfs::SUB_CLASS_OF, &organ),
+            Triple::create(&liver, rdfs::LABEL, Literal::from("liver".to_string())),
+            Triple::create(&liver, rdfs::LABEL, Literal::from("jecur".to_string())),
+            Triple::create(&organ, rdfs::LABEL, Literal::from("organ".to_string())),
+        ]);
+        assert_eq!(graph.len(), 4);
+        assert!(!graph.insert(Triple::create(&liver, rdfs::SUB_CLASS_OF, &organ)));
+        assert!(graph.remove(&Triple::create(&liver, rdfs::SUB_CLASS_OF, &organ)));
+        assert!(!graph.remove(&Triple::create(&liver, rdfs::SUB_CLASS_OF, &organ)));
+        assert_eq!(graph.triples_matching(None, None, Some(&Node::from(&organ))).count(), 0);
+        graph.replace(&liver, rdfs::LABEL, Node::from(Literal::from("hepar".to_string())));
+        assert_eq!(graph.triples_matching(Some(&liver), Some(rdfs::LABEL), None).count(), 1);
+        graph.retain(|triple| triple.subject != organ);
+        assert_eq!(graph.len(), 1);
+        assert_eq!(graph.remove_matching(Some(&liver), None, None), 1);
+        assert!(graph.is_empty());
+        graph.insert(Triple::create(&organ, rdfs::LABEL, Literal::from("organ".to_string())));
+        graph.clear();
+        assert!(graph.is_empty());
+        assert_eq!(graph.triples_matching(None, Some(rdfs::LABEL), None).count(), 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn picks_best_label() {
+        let mut graph = MemoryGraph::new();
+        let liver = Entity::from(obo::Ontology::UBERON.create_iri(2107));
+        let label = |string: &str, tag: &str| {
+            Literal::new(string.to_string(), LiteralTag::lang(tag).unwrap())
+        };
+        graph.add(&liver, rdfs::LABEL, label("Leber", "de"));
+        graph.add(&liver, rdfs::LABEL, label("liver", "en-us"));
+        graph.add(&liver, rdfs::LABEL, Literal::from("jecur".to_string()));
+        assert!(graph.contains(&Triple::create(&liver, rdfs::LABEL, label("liver", "EN-US"))));
+        let best = |languages: &[&str]| graph.best_label(&liver, languages).unwrap().string;
+        assert_eq!(best(&["fr", "de"]), "Leber");
+        assert_eq!(best(&["en"]), "liver");
+        assert_eq!(best(&["en-US", "de"]), "liver");
+        assert_eq!(best(&["fr"]), "jecur");
+        assert!(graph.best_label(&Entity::from(obo::Ontology::UBERON.create_iri(1)), &[])
+            .is_none());
+        assert!(LiteralTag::lang("en_US").is_err());
+    }
+}
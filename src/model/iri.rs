@@ -1,3 +1,4 @@
+use crate::error::PenyuError;
 use std::fmt::Display;
 use strey::Strey;
 
@@ -31,6 +32,326 @@ impl Iri {
     pub fn strip_prefix(&self, iri: &Iri) -> Option<String> {
         self.iri.strip_prefix(&iri.iri).map(|chars| chars.collect::<String>())
     }
+    /// Creates an IRI after checking that it is an absolute IRI as defined by RFC 3987.
+    pub fn parse(string: String) -> Result<Iri, PenyuError> {
+        match validate(&string) {
+            Ok(()) => { Ok(Iri::from(string)) }
+            Err(message) => {
+                Err(PenyuError::from(format!("Invalid IRI <{}>: {}", string, message)))
+            }
+        }
+    }
+    /// An equivalent IRI with lower-case scheme and host, upper-case percent-encodings and
+    /// without dot-segments in the path, so that equivalent IRIs compare equal.
+    pub fn normalize(&self) -> Iri {
+        let string = self.to_string();
+        let parts = Parts::parse(&string);
+        let mut normalized = String::new();
+        if let Some(scheme) = parts.scheme {
+            normalized.push_str(&scheme.to_ascii_lowercase());
+            normalized.push(':');
+        }
+        if let Some(authority) = parts.authority {
+            normalized.push_str("//");
+            let (user_info, host_port) =
+                match authority.rsplit_once('@') {
+                    Some((user_info, host_port)) => { (Some(user_info), host_port) }
+                    None => { (None, authority) }
+                };
+            if let Some(user_info) = user_info {
+                normalized.push_str(&normalize_percent_encodings(user_info));
+                normalized.push('@');
+            }
+            let (host, port) = split_port(host_port);
+            normalized.push_str(&normalize_percent_encodings(&host.to_lowercase()));
+            if let Some(port) = port {
+                normalized.push(':');
+                normalized.push_str(port);
+            }
+        }
+        let path =
+            if parts.scheme.is_some() {
+                remove_dot_segments(parts.path)
+            } else {
+                parts.path.to_string()
+            };
+        normalized.push_str(&normalize_percent_encodings(&path));
+        if let Some(query) = parts.query {
+            normalized.push('?');
+            normalized.push_str(&normalize_percent_encodings(query));
+        }
+        if let Some(fragment) = parts.fragment {
+            normalized.push('#');
+            normalized.push_str(&normalize_percent_encodings(fragment));
+        }
+        Iri::from(normalized)
+    }
+    /// Resolves a reference against this IRI as the base (RFC 3986, section 5.2).
+    pub fn resolve(&self, reference: &str) -> Iri {
+        Iri::from(resolve(&self.to_string(), reference))
+    }
+    /// The shortest relative reference that resolves against this IRI to the given IRI, if
+    /// there is one.
+    pub fn relativize(&self, iri: &Iri) -> Option<String> {
+        let base = self.to_string();
+        let target = iri.to_string();
+        let base_parts = Parts::parse(&base);
+        let target_parts = Parts::parse(&target);
+        if base_parts.scheme.is_none() || base_parts.scheme != target_parts.scheme
+            || base_parts.authority != target_parts.authority {
+            return None;
+        }
+        let mut relative =
+            if target_parts.path != base_parts.path {
+                relative_path(base_parts.path, target_parts.path)
+            } else if target_parts.query.is_none() && base_parts.query.is_some() {
+                match target_parts.path.rsplit('/').next() {
+                    Some("") | None => { "./".to_string() }
+                    Some(segment) => { segment.to_string() }
+                }
+            } else {
+                String::new()
+            };
+        if let Some(query) = target_parts.query {
+            if !relative.is_empty() || target_parts.query != base_parts.query {
+                relative.push('?');
+                relative.push_str(query);
+            }
+        }
+        if let Some(fragment) = target_parts.fragment {
+            relative.push('#');
+            relative.push_str(fragment);
+        }
+        if resolve(&base, &relative) == target { Some(relative) } else { None }
+    }
+}
+
+struct Parts<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl Parts<'_> {
+    fn parse(reference: &str) -> Parts<'_> {
+        let (rest, fragment) =
+            match reference.split_once('#') {
+                Some((rest, fragment)) => { (rest, Some(fragment)) }
+                None => { (reference, None) }
+            };
+        let (rest, query) =
+            match rest.split_once('?') {
+                Some((rest, query)) => { (rest, Some(query)) }
+                None => { (rest, None) }
+            };
+        let (scheme, rest) =
+            match rest.find([':', '/']) {
+                Some(i) if is_scheme(&rest[..i]) && rest[i..].starts_with(':') => {
+                    (Some(&rest[..i]), &rest[i + 1..])
+                }
+                _ => { (None, rest) }
+            };
+        let (authority, path) =
+            match rest.strip_prefix("//") {
+                Some(rest) => {
+                    let end = rest.find('/').unwrap_or(rest.len());
+                    (Some(&rest[..end]), &rest[end..])
+                }
+                None => { (None, rest) }
+            };
+        Parts { scheme, authority, path, query, fragment }
+    }
+}
+
+fn validate(string: &str) -> Result<(), String> {
+    let parts = Parts::parse(string);
+    match parts.scheme {
+        None => { Err("no scheme".to_string())? }
+        Some(scheme) if !is_scheme(scheme) => { Err(format!("invalid scheme {}", scheme))? }
+        Some(_) => {}
+    }
+    if let Some(authority) = parts.authority {
+        let host_port =
+            match authority.rsplit_once('@') {
+                Some((user_info, host_port)) => {
+                    validate_chars(user_info, "user info", |c| is_ipchar(c) && c != '@')?;
+                    host_port
+                }
+                None => { authority }
+            };
+        let (host, port) = split_port(host_port);
+        if let Some(ip_literal) = host.strip_prefix('[') {
+            let Some(ip_literal) = ip_literal.strip_suffix(']') else {
+                Err(format!("invalid host {}", host))?
+            };
+            validate_chars(ip_literal, "host", |c| {
+                c.is_ascii_hexdigit() || matches!(c, ':' | '.' | 'v' | 'V') || is_sub_delim(c)
+                    || is_unreserved(c)
+            })?;
+        } else {
+            validate_chars(host, "host", |c| is_unreserved(c) || is_sub_delim(c))?;
+        }
+        if let Some(port) = port {
+            validate_chars(port, "port", |c| c.is_ascii_digit())?;
+        }
+    }
+    validate_chars(parts.path, "path", |c| is_ipchar(c) || c == '/')?;
+    if let Some(query) = parts.query {
+        validate_chars(query, "query", |c| {
+            is_ipchar(c) || matches!(c, '/' | '?' | '\u{E000}'..='\u{F8FF}'
+                | '\u{F0000}'..='\u{FFFFD}' | '\u{100000}'..='\u{10FFFD}')
+        })?;
+    }
+    if let Some(fragment) = parts.fragment {
+        validate_chars(fragment, "fragment", |c| is_ipchar(c) || matches!(c, '/' | '?'))?;
+    }
+    Ok(())
+}
+
+fn validate_chars<F: Fn(char) -> bool>(string: &str, component: &str, is_valid: F)
+                                        -> Result<(), String> {
+    let mut chars = string.chars();
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            let is_encoding = chars.next().is_some_and(|c| c.is_ascii_hexdigit())
+                && chars.next().is_some_and(|c| c.is_ascii_hexdigit());
+            if !is_encoding {
+                Err(format!("invalid percent-encoding in {}", component))?
+            }
+        } else if !is_valid(c) {
+            Err(format!("invalid character {:?} in {}", c, component))?
+        }
+    }
+    Ok(())
+}
+
+fn is_unreserved(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')
+        || matches!(c, '\u{A0}'..='\u{D7FF}' | '\u{F900}'..='\u{FDCF}' | '\u{FDF0}'..='\u{FFEF}')
+        || (('\u{10000}'..='\u{EFFFD}').contains(&c) && (c as u32) & 0xFFFE != 0xFFFE)
+}
+
+fn is_sub_delim(c: char) -> bool {
+    matches!(c, '!' | '$' | '&' | '\'' | '(' | ')' | '*' | '+' | ',' | ';' | '=')
+}
+
+fn is_ipchar(c: char) -> bool {
+    is_unreserved(c) || is_sub_delim(c) || matches!(c, ':' | '@')
+}
+
+fn split_port(host_port: &str) -> (&str, Option<&str>) {
+    match host_port.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => { (host, Some(port)) }
+        _ => { (host_port, None) }
+    }
+}
+
+fn normalize_percent_encodings(string: &str) -> String {
+    let mut normalized = String::new();
+    let mut chars = string.chars();
+    while let Some(c) = chars.next() {
+        normalized.push(c);
+        if c == '%' {
+            normalized.extend(chars.by_ref().take(2).map(|c| c.to_ascii_uppercase()));
+        }
+    }
+    normalized
+}
+
+fn is_scheme(scheme: &str) -> bool {
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+}
+
+fn resolve(base: &str, reference: &str) -> String {
+    let base = Parts::parse(base);
+    let reference = Parts::parse(reference);
+    let (scheme, authority, path, query) =
+        if reference.scheme.is_some() {
+            (reference.scheme, reference.authority, remove_dot_segments(reference.path),
+             reference.query)
+        } else if reference.authority.is_some() {
+            (base.scheme, reference.authority, remove_dot_segments(reference.path),
+             reference.query)
+        } else if reference.path.is_empty() {
+            (base.scheme, base.authority, base.path.to_string(), reference.query.or(base.query))
+        } else if reference.path.starts_with('/') {
+            (base.scheme, base.authority, remove_dot_segments(reference.path), reference.query)
+        } else {
+            let merged =
+                if base.authority.is_some() && base.path.is_empty() {
+                    format!("/{}", reference.path)
+                } else {
+                    let directory = base.path.rfind('/').map_or("", |i| &base.path[..=i]);
+                    format!("{}{}", directory, reference.path)
+                };
+            (base.scheme, base.authority, remove_dot_segments(&merged), reference.query)
+        };
+    let mut resolved = String::new();
+    if let Some(scheme) = scheme {
+        resolved.push_str(scheme);
+        resolved.push(':');
+    }
+    if let Some(authority) = authority {
+        resolved.push_str("//");
+        resolved.push_str(authority);
+    }
+    resolved.push_str(&path);
+    if let Some(query) = query {
+        resolved.push('?');
+        resolved.push_str(query);
+    }
+    if let Some(fragment) = reference.fragment {
+        resolved.push('#');
+        resolved.push_str(fragment);
+    }
+    resolved
+}
+
+fn remove_dot_segments(path: &str) -> String {
+    let is_absolute = path.starts_with('/');
+    let segments: Vec<&str> = path.split('/').skip(usize::from(is_absolute)).collect();
+    let mut output: Vec<&str> = Vec::new();
+    for (i, segment) in segments.iter().enumerate() {
+        match *segment {
+            "." | ".." => {
+                if *segment == ".." {
+                    output.pop();
+                }
+                if i + 1 == segments.len() {
+                    output.push("");
+                }
+            }
+            _ => { output.push(segment); }
+        }
+    }
+    let joined = output.join("/");
+    if is_absolute { format!("/{}", joined) } else { joined }
+}
+
+/// A path that resolves against the base path to the target path, climbing up with `..`
+/// unless that would go all the way up to the root.
+fn relative_path(base: &str, target: &str) -> String {
+    let base_segments: Vec<&str> = base.split('/').collect();
+    let base_directory = &base_segments[..base_segments.len() - 1];
+    let target_segments: Vec<&str> = target.split('/').collect();
+    let n_common =
+        base_directory.iter().zip(target_segments.iter())
+            .take_while(|(segment1, segment2)| segment1 == segment2).count();
+    let n_up = base_directory.len() - n_common;
+    if n_common <= 1 && n_up > 0 && target.starts_with('/') {
+        return target.to_string();
+    }
+    let rest = target_segments[n_common..].join("/");
+    let mut relative = "../".repeat(n_up);
+    let first_segment = rest.split('/').next().unwrap_or("");
+    if n_up == 0 && (first_segment.is_empty() || first_segment.contains(':')) {
+        relative.push_str("./");
+    }
+    relative.push_str(&rest);
+    relative
 }
 
 impl Display for Iri {
@@ -54,4 +375,81 @@ impl From<&'static str> for Iri {
     fn from(string: &'static str) -> Self {
         Iri { iri: Strey::from(string) }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::iri::Iri;
+
+    #[test]
+    fn resolves_references() {
+        // The examples from RFC 3986, section 5.4.
+        let base = Iri::from("http://a/b/c/d;p?q");
+        let examples = [
+            ("g:h", "g:h"), ("g", "http://a/b/c/g"), ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"), ("/g", "http://a/g"), ("//g", "http://g"),
+            ("?y", "http://a/b/c/d;p?y"), ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"), ("g#s", "http://a/b/c/g#s"),
+            ("g?y#s", "http://a/b/c/g?y#s"), (";x", "http://a/b/c/;x"),
+            ("g;x", "http://a/b/c/g;x"), ("", "http://a/b/c/d;p?q"), (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"), ("..", "http://a/b/"), ("../", "http://a/b/"),
+            ("../g", "http://a/b/g"), ("../..", "http://a/"), ("../../g", "http://a/g"),
+            ("../../../g", "http://a/g"), ("/./g", "http://a/g"), ("/../g", "http://a/g"),
+            ("g.", "http://a/b/c/g."), (".g", "http://a/b/c/.g"), ("g..", "http://a/b/c/g.."),
+            ("..g", "http://a/b/c/..g"), ("./../g", "http://a/b/g"), ("./g/.", "http://a/b/c/g/"),
+            ("g/./h", "http://a/b/c/g/h"), ("g/../h", "http://a/b/c/h"),
+            ("g;x=1/./y", "http://a/b/c/g;x=1/y"), ("g;x=1/../y", "http://a/b/c/y"),
+        ];
+        for (reference, expected) in examples {
+            assert_eq!(base.resolve(reference).to_string(), expected, "{}", reference);
+        }
+    }
+
+    #[test]
+    fn validates() {
+        let valid = [
+            "http://example.org/a/b?c=d#e", "urn:isbn:0451450523", "http://[::1]:8080/x",
+            "http://user@example.org/%C3%A9", "http://example.org/\u{E9}t\u{E9}", "file:///tmp/x",
+        ];
+        for iri in valid {
+            assert!(Iri::parse(iri.to_string()).is_ok(), "{}", iri);
+        }
+        let invalid = [
+            "example.org/a", "http://example.org/a b", "http://example.org/<a>",
+            "http://example.org/%G0", "http://example.org:80a/", "http://example.org/#a#b",
+            "1http://example.org/",
+        ];
+        for iri in invalid {
+            assert!(Iri::parse(iri.to_string()).is_err(), "{}", iri);
+        }
+        assert_eq!(Iri::parse("http://example.org/a b".to_string()).err().unwrap().to_string(),
+                   "Invalid IRI <http://example.org/a b>: invalid character ' ' in path");
+    }
+
+    #[test]
+    fn normalizes() {
+        let iri = Iri::from("HTTP://User@Example.ORG:8080/a/./b/../c%3a?q=%c3%a9#F");
+        assert_eq!(iri.normalize().to_string(), "http://User@example.org:8080/a/c%3A?q=%C3%A9#F");
+        assert!(Iri::from("http://EXAMPLE.org/a/../b").normalize()
+            == Iri::from("http://example.org/b").normalize());
+        let iri = Iri::from("http://%c3%A9XAMPLE.org/");
+        assert_eq!(iri.normalize().to_string(), "http://%C3%A9xample.org/");
+    }
+
+    #[test]
+    fn relativizes() {
+        let base = Iri::from("http://example.org/a/b/c?q");
+        let relativize = |target: &'static str| base.relativize(&Iri::from(target));
+        assert_eq!(relativize("http://example.org/a/b/c?q"), Some("".to_string()));
+        assert_eq!(relativize("http://example.org/a/b/c?q#f"), Some("#f".to_string()));
+        assert_eq!(relativize("http://example.org/a/b/c"), Some("c".to_string()));
+        assert_eq!(relativize("http://example.org/a/b/c?r"), Some("?r".to_string()));
+        assert_eq!(relativize("http://example.org/a/b/d"), Some("d".to_string()));
+        assert_eq!(relativize("http://example.org/a/b/"), Some("./".to_string()));
+        assert_eq!(relativize("http://example.org/a/x/y"), Some("../x/y".to_string()));
+        assert_eq!(relativize("http://example.org/z"), Some("/z".to_string()));
+        assert_eq!(relativize("http://example.org/a/b/x:y"), Some("./x:y".to_string()));
+        assert_eq!(relativize("http://other.org/a/b/c"), None);
+        assert_eq!(relativize("urn:x"), None);
+    }
+}
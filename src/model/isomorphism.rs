@@ -0,0 +1,339 @@
+use crate::model::graph::Graph;
+use crate::model::literal::{Literal, LiteralTag};
+use crate::model::node::{BlankNode, Entity, Node};
+use crate::model::triple::Triple;
+use crate::syntax;
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// The triples that could not be matched between two graphs.
+pub struct GraphDiff {
+    /// Triples of the first graph, with its own blank node labels.
+    pub only_in_first: Vec<Triple>,
+    /// Triples of the second graph, with its own blank node labels.
+    pub only_in_second: Vec<Triple>,
+}
+
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.only_in_first.is_empty() && self.only_in_second.is_empty()
+    }
+}
+
+/// A bijection from the blank nodes of the first graph to those of the second under which
+/// both graphs have the same triples. If there is none, the triples left unmatched by the
+/// closest mapping found.
+pub fn isomorphism<G1: Graph, G2: Graph>(graph1: &G1, graph2: &G2)
+                                         -> Result<BTreeMap<BlankNode, BlankNode>, GraphDiff> {
+    let mut side1 = Side::new(graph1);
+    let mut side2 = Side::new(graph2);
+    refine_colors(&mut side1, &mut side2);
+    let classes1 = side1.color_classes();
+    let classes2 = side2.color_classes();
+    let is_candidate =
+        side1.triples.len() == side2.triples.len()
+            && side1.ground().eq(side2.ground())
+            && classes1.len() == classes2.len()
+            && classes1.iter().all(|(color, class)| {
+                classes2.get(color).is_some_and(|class2| class2.len() == class.len())
+            });
+    if is_candidate {
+        let mut order: Vec<&BlankNode> = side1.colors.keys().collect();
+        order.sort_by_key(|blank_node| {
+            let color = side1.colors[*blank_node];
+            (classes1[&color].len(), color)
+        });
+        let mut search = Search {
+            side1: &side1,
+            side2: &side2,
+            candidates: &classes2,
+            mapping: BTreeMap::new(),
+            used: BTreeSet::new(),
+        };
+        if search.extend(&order) {
+            return Ok(search.mapping);
+        }
+    }
+    let mapping = closest_mapping(&side1, &side2, &classes1, &classes2);
+    let mapped: BTreeSet<Triple> =
+        side1.triples.iter().filter_map(|triple| map_triple(triple, &mapping)).collect();
+    let diff = GraphDiff {
+        only_in_first:
+            side1.triples.iter().filter(|triple| {
+                map_triple(triple, &mapping).is_none_or(|mapped| !side2.triples.contains(&mapped))
+            }).cloned().collect(),
+        only_in_second:
+            side2.triples.iter().filter(|triple| !mapped.contains(triple)).cloned().collect(),
+    };
+    if diff.is_empty() { Ok(mapping) } else { Err(diff) }
+}
+
+pub fn is_isomorphic<G1: Graph, G2: Graph>(graph1: &G1, graph2: &G2) -> bool {
+    isomorphism(graph1, graph2).is_ok()
+}
+
+struct Side {
+    triples: BTreeSet<Triple>,
+    blank_triples: BTreeMap<BlankNode, Vec<Triple>>,
+    colors: BTreeMap<BlankNode, u64>,
+}
+
+impl Side {
+    fn new<G: Graph>(graph: &G) -> Side {
+        let triples: BTreeSet<Triple> = graph.triples().collect();
+        let mut blank_triples: BTreeMap<BlankNode, Vec<Triple>> = BTreeMap::new();
+        for triple in &triples {
+            let blank_nodes: BTreeSet<&BlankNode> = blank_nodes(triple).collect();
+            for blank_node in blank_nodes {
+                blank_triples.entry(blank_node.clone()).or_default().push(triple.clone());
+            }
+        }
+        let colors = blank_triples.keys().map(|blank_node| (blank_node.clone(), 0)).collect();
+        Side { triples, blank_triples, colors }
+    }
+    fn ground(&self) -> impl Iterator<Item=&Triple> {
+        self.triples.iter().filter(|triple| blank_nodes(triple).next().is_none())
+    }
+    fn entity_key(&self, entity: &Entity, focus: &BlankNode) -> String {
+        match entity {
+            Entity::Iri(iri) => { format!("<{}>", iri) }
+            Entity::BlankNode(blank_node) if blank_node == focus => { "@".to_string() }
+            Entity::BlankNode(blank_node) => { format!("_:{}", self.colors[blank_node]) }
+        }
+    }
+    /// The next color of each blank node, derived from its current color and the colors of
+    /// the terms around it.
+    fn refined_colors(&self) -> BTreeMap<BlankNode, u64> {
+        self.blank_triples.iter().map(|(blank_node, triples)| {
+            let mut signatures: Vec<String> =
+                triples.iter().map(|triple| {
+                    let object =
+                        match &triple.object {
+                            Node::Entity(entity) => { self.entity_key(entity, blank_node) }
+                            Node::Literal(literal) => { literal_key(literal) }
+                        };
+                    format!("{} <{}> {}", self.entity_key(&triple.subject, blank_node),
+                            triple.predicate, object)
+                }).collect();
+            signatures.sort();
+            let mut hasher = DefaultHasher::new();
+            self.colors[blank_node].hash(&mut hasher);
+            signatures.hash(&mut hasher);
+            (blank_node.clone(), hasher.finish())
+        }).collect()
+    }
+    fn color_classes(&self) -> BTreeMap<u64, Vec<BlankNode>> {
+        let mut classes: BTreeMap<u64, Vec<BlankNode>> = BTreeMap::new();
+        for (blank_node, color) in &self.colors {
+            classes.entry(*color).or_default().push(blank_node.clone());
+        }
+        classes
+    }
+    fn n_colors(&self) -> usize {
+        self.colors.values().collect::<BTreeSet<_>>().len()
+    }
+}
+
+/// Refines the colors of both sides in lockstep until neither partition gets any finer.
+fn refine_colors(side1: &mut Side, side2: &mut Side) {
+    let max_rounds = side1.colors.len().max(side2.colors.len()) + 1;
+    for _ in 0..max_rounds {
+        let n_colors = (side1.n_colors(), side2.n_colors());
+        side1.colors = side1.refined_colors();
+        side2.colors = side2.refined_colors();
+        if (side1.n_colors(), side2.n_colors()) == n_colors {
+            break;
+        }
+    }
+}
+
+fn literal_key(literal: &Literal) -> String {
+    let string = syntax::escape_string(&literal.string);
+    match &literal.literal_tag {
+        LiteralTag::Type(datatype) => { format!("\"{}\"^^<{}>", string, datatype) }
+        LiteralTag::LangTag(lang_tag) => {
+            format!("\"{}\"@{}", string, lang_tag.to_ascii_lowercase())
+        }
+    }
+}
+
+fn blank_nodes(triple: &Triple) -> impl Iterator<Item=&BlankNode> {
+    let subject =
+        match &triple.subject {
+            Entity::BlankNode(blank_node) => { Some(blank_node) }
+            Entity::Iri(_) => { None }
+        };
+    let object =
+        match &triple.object {
+            Node::Entity(Entity::BlankNode(blank_node)) => { Some(blank_node) }
+            _ => { None }
+        };
+    subject.into_iter().chain(object)
+}
+
+/// The triple with its blank nodes mapped, unless one of them has no image.
+fn map_triple(triple: &Triple, mapping: &BTreeMap<BlankNode, BlankNode>) -> Option<Triple> {
+    let map_entity = |entity: &Entity| match entity {
+        Entity::BlankNode(blank_node) => {
+            mapping.get(blank_node).map(|image| Entity::BlankNode(image.clone()))
+        }
+        Entity::Iri(_) => { Some(entity.clone()) }
+    };
+    let subject = map_entity(&triple.subject)?;
+    let object =
+        match &triple.object {
+            Node::Entity(entity) => { Node::Entity(map_entity(entity)?) }
+            Node::Literal(_) => { triple.object.clone() }
+        };
+    Some(Triple::new(subject, triple.predicate.clone(), object))
+}
+
+/// Backtracking search over blank nodes of the same color.
+struct Search<'a> {
+    side1: &'a Side,
+    side2: &'a Side,
+    candidates: &'a BTreeMap<u64, Vec<BlankNode>>,
+    mapping: BTreeMap<BlankNode, BlankNode>,
+    used: BTreeSet<BlankNode>,
+}
+
+impl Search<'_> {
+    fn extend(&mut self, order: &[&BlankNode]) -> bool {
+        let Some((blank_node, rest)) = order.split_first() else { return true; };
+        let color = self.side1.colors[*blank_node];
+        for candidate in &self.candidates[&color] {
+            if self.used.contains(candidate) {
+                continue;
+            }
+            self.mapping.insert((*blank_node).clone(), candidate.clone());
+            self.used.insert(candidate.clone());
+            if self.is_consistent(blank_node) && self.extend(rest) {
+                return true;
+            }
+            self.mapping.remove(*blank_node);
+            self.used.remove(candidate);
+        }
+        false
+    }
+    /// Whether every triple of the newly mapped blank node whose blank nodes are all mapped
+    /// now is in the second graph.
+    fn is_consistent(&self, blank_node: &BlankNode) -> bool {
+        self.side1.blank_triples[blank_node].iter().all(|triple| {
+            map_triple(triple, &self.mapping).is_none_or(|mapped| {
+                self.side2.triples.contains(&mapped)
+            })
+        })
+    }
+}
+
+/// Pairs blank nodes of the same color, then pairs the rest by how many triples they share
+/// when other blank nodes are ignored.
+fn closest_mapping(side1: &Side, side2: &Side, classes1: &BTreeMap<u64, Vec<BlankNode>>,
+                   classes2: &BTreeMap<u64, Vec<BlankNode>>) -> BTreeMap<BlankNode, BlankNode> {
+    let mut mapping: BTreeMap<BlankNode, BlankNode> = BTreeMap::new();
+    let mut used: BTreeSet<BlankNode> = BTreeSet::new();
+    for (color, class1) in classes1 {
+        let class2 = classes2.get(color).into_iter().flatten();
+        for (blank_node1, blank_node2) in class1.iter().zip(class2) {
+            mapping.insert(blank_node1.clone(), blank_node2.clone());
+            used.insert(blank_node2.clone());
+        }
+    }
+    let local_signatures = |side: &Side, blank_node: &BlankNode| -> BTreeSet<String> {
+        side.blank_triples[blank_node].iter().map(|triple| {
+            let entity_key = |entity: &Entity| match entity {
+                Entity::Iri(iri) => { format!("<{}>", iri) }
+                Entity::BlankNode(other) if other == blank_node => { "@".to_string() }
+                Entity::BlankNode(_) => { "_".to_string() }
+            };
+            let object =
+                match &triple.object {
+                    Node::Entity(entity) => { entity_key(entity) }
+                    Node::Literal(literal) => { literal_key(literal) }
+                };
+            format!("{} <{}> {}", entity_key(&triple.subject), triple.predicate, object)
+        }).collect()
+    };
+    let remaining2: Vec<(&BlankNode, BTreeSet<String>)> =
+        side2.blank_triples.keys().filter(|blank_node| !used.contains(*blank_node))
+            .map(|blank_node| (blank_node, local_signatures(side2, blank_node))).collect();
+    for blank_node1 in side1.blank_triples.keys() {
+        if mapping.contains_key(blank_node1) {
+            continue;
+        }
+        let signatures1 = local_signatures(side1, blank_node1);
+        let best =
+            remaining2.iter().filter(|(blank_node2, _)| !used.contains(*blank_node2))
+                .map(|(blank_node2, signatures2)| {
+                    (signatures1.intersection(signatures2).count(), *blank_node2)
+                }).filter(|(n_shared, _)| *n_shared > 0)
+                .max_by_key(|(n_shared, _)| *n_shared);
+        if let Some((_, blank_node2)) = best {
+            mapping.insert(blank_node1.clone(), blank_node2.clone());
+            used.insert(blank_node2.clone());
+        }
+    }
+    mapping
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::graph::MemoryGraph;
+    use crate::model::iri::Iri;
+    use crate::model::literal::Literal;
+    use crate::model::node::BlankNode;
+    use crate::model::isomorphism;
+
+    fn ex(local: &str) -> Iri {
+        Iri::from(format!("http://example.org/{}", local))
+    }
+
+    fn ring(labels: &[&str]) -> MemoryGraph {
+        let mut graph = MemoryGraph::new();
+        for (i, label) in labels.iter().enumerate() {
+            let next = labels[(i + 1) % labels.len()];
+            graph.add(BlankNode::from(*label), ex("next"), BlankNode::from(next));
+        }
+        graph
+    }
+
+    #[test]
+    fn maps_blank_nodes() {
+        let mut graph1 = MemoryGraph::new();
+        graph1.add(ex("a"), ex("p"), BlankNode::from("node0"));
+        graph1.add(BlankNode::from("node0"), ex("q"), Literal::from("x".to_string()));
+        graph1.add(ex("a"), ex("p"), BlankNode::from("node1"));
+        graph1.add(BlankNode::from("node1"), ex("q"), Literal::from("y".to_string()));
+        let mut graph2 = MemoryGraph::new();
+        graph2.add(ex("a"), ex("p"), BlankNode::from("node1"));
+        graph2.add(BlankNode::from("node1"), ex("q"), Literal::from("x".to_string()));
+        graph2.add(ex("a"), ex("p"), BlankNode::from("node0"));
+        graph2.add(BlankNode::from("node0"), ex("q"), Literal::from("y".to_string()));
+        let mapping = isomorphism::isomorphism(&graph1, &graph2).ok().unwrap();
+        assert!(mapping[&BlankNode::from("node0")] == BlankNode::from("node1"));
+        assert!(mapping[&BlankNode::from("node1")] == BlankNode::from("node0"));
+        // Colors cannot tell apart the nodes of a ring, so this needs the search.
+        assert!(isomorphism::is_isomorphic(&ring(&["a", "b", "c", "d"]),
+                                           &ring(&["w", "x", "y", "z"])));
+        assert!(!isomorphism::is_isomorphic(&ring(&["a", "b", "c", "d"]), &ring(&["x", "y"])));
+    }
+
+    #[test]
+    fn reports_differences() {
+        let mut graph1 = MemoryGraph::new();
+        graph1.add(ex("a"), ex("p"), BlankNode::from("b0"));
+        graph1.add(BlankNode::from("b0"), ex("q"), Literal::from("x".to_string()));
+        graph1.add(BlankNode::from("b0"), ex("r"), Literal::from("y".to_string()));
+        graph1.add(ex("a"), ex("s"), ex("b"));
+        let mut graph2 = MemoryGraph::new();
+        graph2.add(ex("a"), ex("p"), BlankNode::from("c0"));
+        graph2.add(BlankNode::from("c0"), ex("q"), Literal::from("x".to_string()));
+        graph2.add(BlankNode::from("c0"), ex("r"), Literal::from("z".to_string()));
+        graph2.add(ex("a"), ex("s"), ex("b"));
+        let diff = isomorphism::isomorphism(&graph1, &graph2).err().unwrap();
+        assert_eq!(diff.only_in_first.len(), 1);
+        assert!(diff.only_in_first[0].object == Literal::from("y".to_string()).into());
+        assert_eq!(diff.only_in_second.len(), 1);
+        assert!(diff.only_in_second[0].object == Literal::from("z".to_string()).into());
+    }
+}
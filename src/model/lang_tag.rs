@@ -0,0 +1,139 @@
+use crate::error::PenyuError;
+
+const GRANDFATHERED: [&str; 26] = [
+    "en-gb-oed", "i-ami", "i-bnn", "i-default", "i-enochian", "i-hak", "i-klingon", "i-lux",
+    "i-mingo", "i-navajo", "i-pwn", "i-tao", "i-tay", "i-tsu", "sgn-be-fr", "sgn-be-nl",
+    "sgn-ch-de", "art-lojban", "cel-gaulish", "no-bok", "no-nyn", "zh-guoyu", "zh-hakka",
+    "zh-min", "zh-min-nan", "zh-xiang",
+];
+
+/// Whether the tag is a well-formed BCP 47 language tag (RFC 5646, section 2.1).
+pub fn is_well_formed(tag: &str) -> bool {
+    if GRANDFATHERED.iter().any(|grandfathered| grandfathered.eq_ignore_ascii_case(tag)) {
+        return true;
+    }
+    let subtags: Vec<&str> = tag.split('-').collect();
+    let is_alphanumeric = |subtag: &&str| {
+        (1..=8).contains(&subtag.len()) && subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+    };
+    if !subtags.iter().all(is_alphanumeric) {
+        return false;
+    }
+    let is_alpha = |subtag: &str, lens: std::ops::RangeInclusive<usize>| {
+        lens.contains(&subtag.len()) && subtag.bytes().all(|b| b.is_ascii_alphabetic())
+    };
+    if subtags[0].eq_ignore_ascii_case("x") {
+        return subtags.len() > 1;
+    }
+    if !is_alpha(subtags[0], 2..=8) {
+        return false;
+    }
+    let mut i: usize = 1;
+    if subtags[0].len() <= 3 {
+        let mut n_extlangs: usize = 0;
+        while n_extlangs < 3 && subtags.get(i).is_some_and(|subtag| is_alpha(subtag, 3..=3)) {
+            n_extlangs += 1;
+            i += 1;
+        }
+    }
+    if subtags.get(i).is_some_and(|subtag| is_alpha(subtag, 4..=4)) {
+        i += 1;
+    }
+    let is_region = |subtag: &str| {
+        is_alpha(subtag, 2..=2)
+            || (subtag.len() == 3 && subtag.bytes().all(|b| b.is_ascii_digit()))
+    };
+    if subtags.get(i).is_some_and(|subtag| is_region(subtag)) {
+        i += 1;
+    }
+    let is_variant = |subtag: &str| {
+        (5..=8).contains(&subtag.len())
+            || (subtag.len() == 4 && subtag.starts_with(|c: char| c.is_ascii_digit()))
+    };
+    while subtags.get(i).is_some_and(|subtag| is_variant(subtag)) {
+        i += 1;
+    }
+    while let Some(singleton) = subtags.get(i).filter(|subtag| subtag.len() == 1) {
+        if singleton.eq_ignore_ascii_case("x") {
+            return subtags.len() > i + 1;
+        }
+        i += 1;
+        let start = i;
+        while subtags.get(i).is_some_and(|subtag| subtag.len() >= 2) {
+            i += 1;
+        }
+        if i == start {
+            return false;
+        }
+    }
+    i == subtags.len()
+}
+
+/// The tag with the case conventions of RFC 5646, section 2.1.1: lower-case language,
+/// title-case script and upper-case region.
+pub fn normalize(tag: &str) -> String {
+    let mut normalized: Vec<String> = Vec::new();
+    let mut after_singleton = false;
+    for (i, subtag) in tag.split('-').enumerate() {
+        let subtag =
+            if i == 0 || after_singleton {
+                subtag.to_ascii_lowercase()
+            } else if subtag.len() == 2 {
+                subtag.to_ascii_uppercase()
+            } else if subtag.len() == 4 && subtag.bytes().all(|b| b.is_ascii_alphabetic()) {
+                let lower = subtag.to_ascii_lowercase();
+                format!("{}{}", lower[..1].to_ascii_uppercase(), &lower[1..])
+            } else {
+                subtag.to_ascii_lowercase()
+            };
+        after_singleton = after_singleton || (i > 0 && subtag.len() == 1);
+        normalized.push(subtag);
+    }
+    normalized.join("-")
+}
+
+/// Validates the tag and normalizes its case.
+pub fn parse(tag: &str) -> Result<String, PenyuError> {
+    if is_well_formed(tag) {
+        Ok(normalize(tag))
+    } else {
+        Err(PenyuError::from(format!("Invalid language tag {}", tag)))
+    }
+}
+
+/// Whether the tag matches the language range by basic filtering (RFC 4647, section 3.3.1),
+/// i.e. whether it is the range or starts with the range followed by a hyphen, ignoring case.
+pub fn matches(tag: &str, range: &str) -> bool {
+    range == "*"
+        || (tag.get(..range.len()).is_some_and(|head| head.eq_ignore_ascii_case(range))
+        && (tag.len() == range.len() || tag[range.len()..].starts_with('-')))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::lang_tag;
+
+    #[test]
+    fn validates_and_normalizes() {
+        let valid = [
+            "en", "en-US", "zh-Hant-TW", "sr-Latn-RS", "de-CH-1996", "es-419", "zh-yue-HK",
+            "en-a-bbb-x-a-ccc", "x-whatever", "i-klingon", "EN-gb-OED", "sl-rozaj-biske",
+        ];
+        for tag in valid {
+            assert!(lang_tag::is_well_formed(tag), "{}", tag);
+        }
+        let invalid = ["", "e", "en-", "en_US", "123", "en-a", "en-US-a-x", "de-419-DE-x",
+            "abcdefghi", "en-x-abcdefghi"];
+        for tag in invalid {
+            assert!(!lang_tag::is_well_formed(tag), "{}", tag);
+        }
+        assert_eq!(lang_tag::normalize("ZH-hant-tw"), "zh-Hant-TW");
+        assert_eq!(lang_tag::normalize("en-a-bb-x-US"), "en-a-bb-x-us");
+        assert_eq!(lang_tag::parse("EN-us").unwrap(), "en-US");
+        assert!(lang_tag::parse("en_US").is_err());
+        assert!(lang_tag::matches("en-US", "en"));
+        assert!(lang_tag::matches("en-us", "EN-US"));
+        assert!(!lang_tag::matches("eng", "en"));
+        assert!(lang_tag::matches("fr", "*"));
+    }
+}
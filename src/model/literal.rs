@@ -1,12 +1,52 @@
+use crate::error::PenyuError;
 use crate::model::iri::Iri;
+use crate::model::lang_tag;
+use crate::model::value::Value;
+use std::cmp::Ordering;
 use crate::vocabs;
 
-#[derive(Clone, Ord, PartialOrd, PartialEq, Eq)]
+/// Language tags compare case-insensitively, so `@en-US` and `@en-us` are the same tag.
+#[derive(Clone)]
 pub enum LiteralTag {
     Type(Iri),
     LangTag(String)
 }
 
+impl LiteralTag {
+    /// A language tag, validated as BCP 47 and with normalized case.
+    pub fn lang(tag: &str) -> Result<LiteralTag, PenyuError> {
+        Ok(LiteralTag::LangTag(lang_tag::parse(tag)?))
+    }
+}
+
+impl Ord for LiteralTag {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (LiteralTag::Type(type1), LiteralTag::Type(type2)) => { type1.cmp(type2) }
+            (LiteralTag::Type(_), LiteralTag::LangTag(_)) => { Ordering::Less }
+            (LiteralTag::LangTag(_), LiteralTag::Type(_)) => { Ordering::Greater }
+            (LiteralTag::LangTag(tag1), LiteralTag::LangTag(tag2)) => {
+                tag1.bytes().map(|b| b.to_ascii_lowercase())
+                    .cmp(tag2.bytes().map(|b| b.to_ascii_lowercase()))
+            }
+        }
+    }
+}
+
+impl PartialOrd for LiteralTag {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for LiteralTag {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for LiteralTag {}
+
 #[derive(Clone, Ord, PartialOrd, PartialEq, Eq)]
 pub struct Literal {
     pub string: String,
@@ -17,6 +57,32 @@ impl Literal {
     pub fn new(string: String, literal_tag: LiteralTag) -> Literal {
         Literal { string, literal_tag }
     }
+    fn typed<S: ToString>(value: S, datatype: &Iri) -> Literal {
+        Literal::new(value.to_string(), LiteralTag::Type(datatype.clone()))
+    }
+    /// The typed value, if the datatype is supported, or an error if the lexical form is
+    /// invalid for the datatype.
+    pub fn value(&self) -> Result<Option<Value>, PenyuError> {
+        match &self.literal_tag {
+            LiteralTag::Type(datatype) => { Value::parse(&self.string, datatype) }
+            LiteralTag::LangTag(_) => { Ok(None) }
+        }
+    }
+    pub fn is_valid(&self) -> bool {
+        self.value().is_ok()
+    }
+    /// The same literal with the canonical lexical form, if the datatype is supported.
+    pub fn canonical(&self) -> Result<Literal, PenyuError> {
+        match self.value()? {
+            Some(value) => { Ok(Literal::new(value.to_string(), self.literal_tag.clone())) }
+            None => { Ok(self.clone()) }
+        }
+    }
+    fn expect_value(&self) -> Result<Value, PenyuError> {
+        self.value()?.ok_or_else(|| {
+            PenyuError::from(format!("Literal \"{}\" has no supported datatype", self.string))
+        })
+    }
 }
 
 impl From<String> for Literal {
@@ -31,4 +97,93 @@ impl From<f64> for Literal {
             string: float.to_string(), literal_tag: LiteralTag::Type(vocabs::xsd::DOUBLE.clone())
         }
     }
-}
\ No newline at end of file
+}
+
+impl From<f32> for Literal {
+    fn from(float: f32) -> Self {
+        Literal::typed(float, vocabs::xsd::FLOAT)
+    }
+}
+
+/// All Rust integer types map to `xsd:integer`, so equal numbers give equal literals.
+impl From<i64> for Literal {
+    fn from(integer: i64) -> Self {
+        Literal::typed(integer, vocabs::xsd::INTEGER)
+    }
+}
+
+impl From<i32> for Literal {
+    fn from(integer: i32) -> Self {
+        Literal::typed(integer, vocabs::xsd::INTEGER)
+    }
+}
+
+impl From<u64> for Literal {
+    fn from(integer: u64) -> Self {
+        Literal::typed(integer, vocabs::xsd::INTEGER)
+    }
+}
+
+impl From<u32> for Literal {
+    fn from(integer: u32) -> Self {
+        Literal::typed(integer, vocabs::xsd::INTEGER)
+    }
+}
+
+impl From<bool> for Literal {
+    fn from(boolean: bool) -> Self {
+        Literal::typed(boolean, vocabs::xsd::BOOLEAN)
+    }
+}
+
+impl From<Value> for Literal {
+    fn from(value: Value) -> Self {
+        Literal::typed(&value, value.datatype())
+    }
+}
+
+impl TryFrom<&Literal> for i64 {
+    type Error = PenyuError;
+    fn try_from(literal: &Literal) -> Result<Self, Self::Error> {
+        match literal.expect_value()? {
+            Value::Integer(integer) => {
+                integer.as_i128().and_then(|n| i64::try_from(n).ok()).ok_or_else(|| {
+                    PenyuError::from(format!("{} is out of range for i64", integer))
+                })
+            }
+            value => { Err(PenyuError::from(format!("{} is not an integer", value))) }
+        }
+    }
+}
+
+impl TryFrom<&Literal> for u64 {
+    type Error = PenyuError;
+    fn try_from(literal: &Literal) -> Result<Self, Self::Error> {
+        match literal.expect_value()? {
+            Value::Integer(integer) => {
+                integer.as_i128().and_then(|n| u64::try_from(n).ok()).ok_or_else(|| {
+                    PenyuError::from(format!("{} is out of range for u64", integer))
+                })
+            }
+            value => { Err(PenyuError::from(format!("{} is not an integer", value))) }
+        }
+    }
+}
+
+impl TryFrom<&Literal> for bool {
+    type Error = PenyuError;
+    fn try_from(literal: &Literal) -> Result<Self, Self::Error> {
+        match literal.expect_value()? {
+            Value::Boolean(boolean) => { Ok(boolean) }
+            value => { Err(PenyuError::from(format!("{} is not a boolean", value))) }
+        }
+    }
+}
+
+impl TryFrom<&Literal> for f64 {
+    type Error = PenyuError;
+    fn try_from(literal: &Literal) -> Result<Self, Self::Error> {
+        let value = literal.expect_value()?;
+        value.as_f64().ok_or_else(|| PenyuError::from(format!("{} is not a number", value)))
+    }
+}
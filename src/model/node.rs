@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use crate::model::literal::Literal;
 use crate::model::iri::Iri;
@@ -19,8 +20,33 @@ pub struct BlankNode {
     id: Arc<String>
 }
 
+impl Node {
+    /// The node with its blank node relabeled, as in `BlankNode::relabel`.
+    pub fn relabel(&self, labels: &BTreeMap<BlankNode, BlankNode>) -> Node {
+        match self {
+            Node::Entity(entity) => { Node::Entity(entity.relabel(labels)) }
+            Node::Literal(_) => { self.clone() }
+        }
+    }
+}
+
+impl Entity {
+    /// The entity with its blank node relabeled, as in `BlankNode::relabel`.
+    pub fn relabel(&self, labels: &BTreeMap<BlankNode, BlankNode>) -> Entity {
+        match self {
+            Entity::BlankNode(blank_node) => { Entity::BlankNode(blank_node.relabel(labels)) }
+            Entity::Iri(_) => { self.clone() }
+        }
+    }
+}
+
 impl BlankNode {
     pub fn id(&self) -> &str { self.id.as_str() }
+    /// The blank node the labels map this one to, or this one if unmapped. Labels are shared,
+    /// not copied, so relabeling many triples allocates no strings.
+    pub fn relabel(&self, labels: &BTreeMap<BlankNode, BlankNode>) -> BlankNode {
+        labels.get(self).unwrap_or(self).clone()
+    }
 }
 
 impl From<&Node> for Node {
@@ -62,6 +88,17 @@ impl From<&Iri> for Node {
     }
 }
 
+impl From<BlankNode> for Node {
+    fn from(blank_node: BlankNode) -> Self {
+        Node::Entity(Entity::BlankNode(blank_node))
+    }
+}
+impl From<&BlankNode> for Node {
+    fn from(blank_node: &BlankNode) -> Self {
+        Node::Entity(Entity::BlankNode(blank_node.clone()))
+    }
+}
+
 impl From<&Entity> for Entity {
     fn from(entity: &Entity) -> Self {
         entity.clone()
@@ -0,0 +1,45 @@
+use crate::model::iri::Iri;
+use crate::model::node::{BlankNode, Entity, Node};
+use crate::model::triple::Triple;
+use std::collections::BTreeMap;
+
+/// A triple in a dataset, together with the name of the graph it belongs to, or `None` for the
+/// default graph.
+#[derive(Clone, Ord, PartialOrd, PartialEq, Eq)]
+pub struct Quad {
+    pub subject: Entity,
+    pub predicate: Iri,
+    pub object: Node,
+    pub graph_name: Option<Entity>,
+}
+
+impl Quad {
+    pub fn new(subject: Entity, predicate: Iri, object: Node, graph_name: Option<Entity>)
+               -> Self {
+        Quad { subject, predicate, object, graph_name }
+    }
+    pub fn create<S, P, O>(subject: S, predicate: P, object: O, graph_name: Option<Entity>)
+                           -> Self
+    where
+        S: Into<Entity>,
+        P: Into<Iri>,
+        O: Into<Node>,
+    {
+        Quad::new(subject.into(), predicate.into(), object.into(), graph_name)
+    }
+    pub fn from_triple(triple: Triple, graph_name: Option<Entity>) -> Self {
+        Quad::new(triple.subject, triple.predicate, triple.object, graph_name)
+    }
+    pub fn triple(&self) -> Triple {
+        Triple::new(self.subject.clone(), self.predicate.clone(), self.object.clone())
+    }
+    pub fn into_triple(self) -> Triple {
+        Triple::new(self.subject, self.predicate, self.object)
+    }
+    /// The quad with its blank nodes relabeled, as in `BlankNode::relabel`.
+    pub fn relabel(&self, labels: &BTreeMap<BlankNode, BlankNode>) -> Quad {
+        let graph_name = self.graph_name.as_ref().map(|graph_name| graph_name.relabel(labels));
+        Quad::new(self.subject.relabel(labels), self.predicate.clone(),
+                  self.object.relabel(labels), graph_name)
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::{BTreeMap, BTreeSet};
+use crate::model::graph::{Graph, MemoryGraph};
+use crate::model::iri::Iri;
+use crate::model::node::{BlankNode, Entity, Node};
+use crate::model::triple::Triple;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum BlankNodeHandling {
+    /// Blank nodes with the same id in both graphs are the same node.
+    Keep,
+    /// Blank nodes of the second graph whose ids also occur in the first graph are renamed,
+    /// so the graphs share no blank nodes.
+    RenameOnCollision,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PrefixConflict {
+    /// Keep the first graph's namespace for a prefix bound in both graphs.
+    KeepFirst,
+    /// Use the second graph's namespace for a prefix bound in both graphs.
+    KeepSecond,
+    /// Keep the first graph's namespace and bind the second graph's namespace to the prefix
+    /// with a number appended, unless it is already bound to another prefix.
+    RenameSecond,
+}
+
+#[derive(Clone)]
+pub struct SetOperationConfig {
+    pub blank_nodes: BlankNodeHandling,
+    pub prefix_conflict: PrefixConflict,
+}
+
+impl Default for SetOperationConfig {
+    fn default() -> Self {
+        SetOperationConfig {
+            blank_nodes: BlankNodeHandling::Keep,
+            prefix_conflict: PrefixConflict::KeepFirst,
+        }
+    }
+}
+
+/// All triples of either graph, with the merged prefixes of both graphs.
+pub fn union<G1: Graph, G2: Graph>(graph1: &G1, graph2: &G2, config: &SetOperationConfig)
+                                   -> MemoryGraph {
+    let labels = second_labels(graph1, graph2, config);
+    let mut result = merged_header(graph1, graph2, config);
+    for triple in graph1.triples() {
+        result.add_triple(triple);
+    }
+    for triple in graph2.triples() {
+        result.add_triple(triple.relabel(&labels));
+    }
+    result
+}
+
+/// The triples of the first graph that are also in the second graph, with the merged
+/// prefixes of both graphs.
+pub fn intersection<G1: Graph, G2: Graph>(graph1: &G1, graph2: &G2, config: &SetOperationConfig)
+                                          -> MemoryGraph {
+    let labels = second_labels(graph1, graph2, config);
+    let mut result = merged_header(graph1, graph2, config);
+    for triple in graph1.triples().filter(|triple| is_shared(triple, graph2, &labels)) {
+        result.add_triple(triple);
+    }
+    result
+}
+
+/// The triples of the first graph that are not in the second graph, with the first graph's
+/// base and prefixes.
+pub fn difference<G1: Graph, G2: Graph>(graph1: &G1, graph2: &G2, config: &SetOperationConfig)
+                                        -> MemoryGraph {
+    let labels = second_labels(graph1, graph2, config);
+    let mut result = MemoryGraph::new();
+    if let Some(base_ns) = graph1.base_ns() {
+        result.set_base_ns(base_ns.clone());
+    }
+    for (prefix, iri) in graph1.prefixes() {
+        result.add_prefix(prefix.clone(), iri.clone());
+    }
+    for triple in graph1.triples().filter(|triple| !is_shared(triple, graph2, &labels)) {
+        result.add_triple(triple);
+    }
+    result
+}
+
+/// Whether the second graph contains the triple, where blank nodes renamed in the second
+/// graph never match the first graph's blank nodes of the same id.
+fn is_shared<G: Graph>(triple: &Triple, graph2: &G, labels: &BTreeMap<BlankNode, BlankNode>)
+                       -> bool {
+    let is_renamed =
+        blank_nodes(triple).iter().any(|blank_node| labels.contains_key(blank_node));
+    !is_renamed && graph2.contains(triple)
+}
+
+fn blank_nodes(triple: &Triple) -> Vec<&BlankNode> {
+    let mut blank_nodes: Vec<&BlankNode> = Vec::new();
+    if let Entity::BlankNode(blank_node) = &triple.subject {
+        blank_nodes.push(blank_node);
+    }
+    if let Node::Entity(Entity::BlankNode(blank_node)) = &triple.object {
+        blank_nodes.push(blank_node);
+    }
+    blank_nodes
+}
+
+fn graph_blank_nodes<G: Graph>(graph: &G) -> BTreeSet<BlankNode> {
+    let mut graph_blank_nodes: BTreeSet<BlankNode> = BTreeSet::new();
+    for triple in graph.triples() {
+        graph_blank_nodes.extend(blank_nodes(&triple).into_iter().cloned());
+    }
+    graph_blank_nodes
+}
+
+/// New labels for the blank nodes of the second graph that collide with the first graph's,
+/// if the configuration asks for renaming.
+fn second_labels<G1: Graph, G2: Graph>(graph1: &G1, graph2: &G2, config: &SetOperationConfig)
+                                       -> BTreeMap<BlankNode, BlankNode> {
+    let mut labels: BTreeMap<BlankNode, BlankNode> = BTreeMap::new();
+    if config.blank_nodes == BlankNodeHandling::Keep {
+        return labels;
+    }
+    let ids1 = graph_blank_nodes(graph1);
+    let ids2 = graph_blank_nodes(graph2);
+    let mut used: BTreeSet<String> =
+        ids1.iter().chain(ids2.iter()).map(|blank_node| blank_node.id().to_string()).collect();
+    for blank_node in ids2.intersection(&ids1) {
+        let fresh =
+            (1..).map(|i| format!("{}_{}", blank_node.id(), i)).find(|id| !used.contains(id))
+                .unwrap();
+        used.insert(fresh.clone());
+        labels.insert(blank_node.clone(), BlankNode::from(fresh));
+    }
+    labels
+}
+
+/// A graph with the first graph's base, else the second's, and the merged prefixes.
+fn merged_header<G1: Graph, G2: Graph>(graph1: &G1, graph2: &G2, config: &SetOperationConfig)
+                                       -> MemoryGraph {
+    let mut result = MemoryGraph::new();
+    if let Some(base_ns) = graph1.base_ns().as_ref().or(graph2.base_ns().as_ref()) {
+        result.set_base_ns(base_ns.clone());
+    }
+    for (prefix, iri) in merge_prefixes(graph1.prefixes(), graph2.prefixes(),
+                                        config.prefix_conflict) {
+        result.add_prefix(prefix, iri);
+    }
+    result
+}
+
+fn merge_prefixes(prefixes1: &BTreeMap<String, Iri>, prefixes2: &BTreeMap<String, Iri>,
+                  conflict: PrefixConflict) -> BTreeMap<String, Iri> {
+    let mut merged: BTreeMap<String, Iri> =
+        prefixes1.iter().map(|(prefix, iri)| (prefix.clone(), iri.clone())).collect();
+    for (prefix, iri) in prefixes2 {
+        match merged.get(prefix) {
+            None => { merged.insert(prefix.clone(), iri.clone()); }
+            Some(iri1) if iri1 == iri => {}
+            Some(_) => {
+                match conflict {
+                    PrefixConflict::KeepFirst => {}
+                    PrefixConflict::KeepSecond => {
+                        merged.insert(prefix.clone(), iri.clone());
+                    }
+                    PrefixConflict::RenameSecond => {
+                        if merged.values().any(|bound| bound == iri) {
+                            continue;
+                        }
+                        let renamed =
+                            (1..).map(|i| format!("{}{}", prefix, i))
+                                .find(|renamed| {
+                                    !merged.contains_key(renamed)
+                                        && !prefixes2.contains_key(renamed)
+                                }).unwrap();
+                        merged.insert(renamed, iri.clone());
+                    }
+                }
+            }
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::graph::{Graph, MemoryGraph};
+    use crate::model::iri::Iri;
+    use crate::model::literal::Literal;
+    use crate::model::node::BlankNode;
+    use crate::model::set_ops::{BlankNodeHandling, PrefixConflict, SetOperationConfig};
+    use crate::model::triple::Triple;
+    use crate::vocabs::{obo, rdfs};
+
+    fn graphs() -> (MemoryGraph, MemoryGraph) {
+        let liver = obo::ns::UBERON.append("0002107".to_string());
+        let heart = obo::ns::UBERON.append("0000948".to_string());
+        let mut graph1 = MemoryGraph::new();
+        graph1.add_prefix("ex".to_string(), Iri::from("http://example.org/a/"));
+        graph1.add(&liver, rdfs::LABEL, Literal::from("liver".to_string()));
+        graph1.add(&liver, rdfs::SEE_ALSO, BlankNode::from("b0"));
+        graph1.add(BlankNode::from("b0"), rdfs::LABEL, Literal::from("note".to_string()));
+        let mut graph2 = MemoryGraph::new();
+        graph2.add_prefix("ex".to_string(), Iri::from("http://example.org/b/"));
+        graph2.add_prefix("obo".to_string(), obo::NAMESPACE.clone());
+        graph2.add(&liver, rdfs::LABEL, Literal::from("liver".to_string()));
+        graph2.add(&heart, rdfs::LABEL, Literal::from("heart".to_string()));
+        graph2.add(BlankNode::from("b0"), rdfs::LABEL, Literal::from("note".to_string()));
+        (graph1, graph2)
+    }
+
+    #[test]
+    fn keeps_blank_nodes() {
+        let (graph1, graph2) = graphs();
+        let config = SetOperationConfig::default();
+        let union = super::union(&graph1, &graph2, &config);
+        assert_eq!(union.len(), 4);
+        assert!(union.prefixes()["ex"] == Iri::from("http://example.org/a/"));
+        assert_eq!(union.prefixes().len(), 2);
+        let intersection = super::intersection(&graph1, &graph2, &config);
+        assert_eq!(intersection.len(), 2);
+        let difference = super::difference(&graph1, &graph2, &config);
+        assert_eq!(difference.len(), 1);
+        assert!(difference.contains(&Triple::create(obo::ns::UBERON.append("0002107".to_string()),
+                                                    rdfs::SEE_ALSO, BlankNode::from("b0"))));
+        assert_eq!(difference.prefixes().len(), 1);
+    }
+
+    #[test]
+    fn renames_blank_nodes_and_prefixes() {
+        let (graph1, graph2) = graphs();
+        let config = SetOperationConfig {
+            blank_nodes: BlankNodeHandling::RenameOnCollision,
+            prefix_conflict: PrefixConflict::RenameSecond,
+        };
+        let union = super::union(&graph1, &graph2, &config);
+        assert_eq!(union.len(), 5);
+        assert!(union.contains(&Triple::create(BlankNode::from("b0_1"), rdfs::LABEL,
+                                               Literal::from("note".to_string()))));
+        assert!(union.prefixes()["ex1"] == Iri::from("http://example.org/b/"));
+        assert_eq!(super::intersection(&graph1, &graph2, &config).len(), 1);
+        assert_eq!(super::difference(&graph1, &graph2, &config).len(), 2);
+        let config = SetOperationConfig {
+            prefix_conflict: PrefixConflict::KeepSecond,
+            ..config
+        };
+        let union = super::union(&graph1, &graph2, &config);
+        assert!(union.prefixes()["ex"] == Iri::from("http://example.org/b/"));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::model::iri::Iri;
-use crate::model::node::{Entity, Node};
+use crate::model::node::{BlankNode, Entity, Node};
+use std::collections::BTreeMap;
 
 #[derive(Clone, Ord, PartialOrd, PartialEq, Eq)]
 pub struct Triple {
@@ -20,4 +21,9 @@ impl Triple {
     {
         Triple::new(subject.into(), predicate.into(), object.into())
     }
+    /// The triple with its blank nodes relabeled, as in `BlankNode::relabel`.
+    pub fn relabel(&self, labels: &BTreeMap<BlankNode, BlankNode>) -> Triple {
+        Triple::new(self.subject.relabel(labels), self.predicate.clone(),
+                    self.object.relabel(labels))
+    }
 }
\ No newline at end of file
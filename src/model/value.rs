@@ -0,0 +1,688 @@
+use crate::error::PenyuError;
+use crate::model::iri::Iri;
+use crate::vocabs::xsd;
+use std::fmt::{Display, Formatter};
+
+/// The value of a literal of one of the supported XSD datatypes.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+    Integer(Integer),
+    Decimal(Decimal),
+    Double(f64),
+    Float(f32),
+    Boolean(bool),
+    Date(Date),
+    DateTime(DateTime),
+    Duration(Duration),
+    AnyUri(String),
+}
+
+/// An integer of any size, kept as its digits without leading zeros. Zero is not negative.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Integer {
+    pub negative: bool,
+    pub digits: String,
+}
+
+/// A decimal number, kept exactly as digits before and after the decimal point.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Decimal {
+    pub negative: bool,
+    pub integer_digits: String,
+    pub fraction_digits: String,
+}
+
+/// Timezones are offsets from UTC in minutes.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Date {
+    pub year: i64,
+    pub month: u8,
+    pub day: u8,
+    pub timezone: Option<i16>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DateTime {
+    pub year: i64,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub nanosecond: u32,
+    pub timezone: Option<i16>,
+}
+
+/// Which of the duration datatypes a duration belongs to, which decides its canonical form.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DurationKind {
+    Duration,
+    YearMonthDuration,
+    DayTimeDuration,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Duration {
+    pub kind: DurationKind,
+    pub negative: bool,
+    pub months: u64,
+    pub seconds: u64,
+    pub nanoseconds: u32,
+}
+
+impl Value {
+    /// Parses a lexical form of the datatype, or returns `None` if the datatype is not
+    /// supported.
+    pub fn parse(lexical: &str, datatype: &Iri) -> Result<Option<Value>, PenyuError> {
+        let trimmed = lexical.trim_matches([' ', '\t', '\n', '\r']);
+        let value =
+            if let Some((min, max)) = integer_range(datatype) {
+                parse_integer(trimmed).filter(|integer| integer.is_within(min, max))
+                    .map(Value::Integer)
+            } else if datatype == xsd::DECIMAL {
+                parse_decimal(trimmed).map(Value::Decimal)
+            } else if datatype == xsd::DOUBLE {
+                parse_double(trimmed).map(Value::Double)
+            } else if datatype == xsd::FLOAT {
+                parse_double(trimmed).map(|x| Value::Float(x as f32))
+            } else if datatype == xsd::BOOLEAN {
+                match trimmed {
+                    "true" | "1" => { Some(Value::Boolean(true)) }
+                    "false" | "0" => { Some(Value::Boolean(false)) }
+                    _ => { None }
+                }
+            } else if datatype == xsd::DATE {
+                parse_date(trimmed).map(Value::Date)
+            } else if datatype == xsd::DATE_TIME {
+                parse_date_time(trimmed).map(Value::DateTime)
+            } else if datatype == xsd::DURATION {
+                parse_duration(trimmed, DurationKind::Duration).map(Value::Duration)
+            } else if datatype == xsd::YEAR_MONTH_DURATION {
+                parse_duration(trimmed, DurationKind::YearMonthDuration).map(Value::Duration)
+            } else if datatype == xsd::DAY_TIME_DURATION {
+                parse_duration(trimmed, DurationKind::DayTimeDuration).map(Value::Duration)
+            } else if datatype == xsd::ANY_URI {
+                Some(Value::AnyUri(lexical.split_whitespace().collect::<Vec<&str>>().join(" ")))
+            } else {
+                return Ok(None);
+            };
+        match value {
+            Some(value) => { Ok(Some(value)) }
+            None => {
+                Err(PenyuError::from(
+                    format!("Invalid lexical form \"{}\" for datatype {}", lexical, datatype)
+                ))
+            }
+        }
+    }
+    /// The most general datatype for this kind of value, except that durations keep their
+    /// duration datatype.
+    pub fn datatype(&self) -> &'static Iri {
+        match self {
+            Value::Integer(_) => { xsd::INTEGER }
+            Value::Decimal(_) => { xsd::DECIMAL }
+            Value::Double(_) => { xsd::DOUBLE }
+            Value::Float(_) => { xsd::FLOAT }
+            Value::Boolean(_) => { xsd::BOOLEAN }
+            Value::Date(_) => { xsd::DATE }
+            Value::DateTime(_) => { xsd::DATE_TIME }
+            Value::Duration(duration) => {
+                match duration.kind {
+                    DurationKind::Duration => { xsd::DURATION }
+                    DurationKind::YearMonthDuration => { xsd::YEAR_MONTH_DURATION }
+                    DurationKind::DayTimeDuration => { xsd::DAY_TIME_DURATION }
+                }
+            }
+            Value::AnyUri(_) => { xsd::ANY_URI }
+        }
+    }
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Integer(integer) => { Some(integer.as_f64()) }
+            Value::Decimal(decimal) => { Some(decimal.as_f64()) }
+            Value::Double(x) => { Some(*x) }
+            Value::Float(x) => { Some(*x as f64) }
+            _ => { None }
+        }
+    }
+}
+
+/// Writes the canonical lexical form.
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Integer(integer) => { write!(f, "{}", integer) }
+            Value::Decimal(decimal) => { write!(f, "{}", decimal) }
+            Value::Double(x) => { write!(f, "{}", canonical_double(*x)) }
+            Value::Float(x) => { write!(f, "{}", canonical_double(*x)) }
+            Value::Boolean(b) => { write!(f, "{}", b) }
+            Value::Date(date) => { write!(f, "{}", date) }
+            Value::DateTime(date_time) => { write!(f, "{}", date_time) }
+            Value::Duration(duration) => { write!(f, "{}", duration) }
+            Value::AnyUri(uri) => { write!(f, "{}", uri) }
+        }
+    }
+}
+
+impl Integer {
+    /// The integer, if it fits in an `i128`.
+    pub fn as_i128(&self) -> Option<i128> {
+        format!("{}", self).parse().ok()
+    }
+    pub fn as_f64(&self) -> f64 {
+        format!("{}", self).parse().unwrap_or(f64::NAN)
+    }
+    /// Whether the integer is within the bounds, where `None` means unbounded.
+    fn is_within(&self, min: Option<i128>, max: Option<i128>) -> bool {
+        // An integer too large for an i128 is beyond any i128 bound on the side of its sign.
+        let n = self.as_i128();
+        min.is_none_or(|min| n.map_or(!self.negative, |n| n >= min))
+            && max.is_none_or(|max| n.map_or(self.negative, |n| n <= max))
+    }
+}
+
+impl Display for Integer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.negative { "-" } else { "" };
+        write!(f, "{}{}", sign, self.digits)
+    }
+}
+
+impl Decimal {
+    pub fn as_f64(&self) -> f64 {
+        let string = format!("{}", self);
+        string.parse().unwrap_or(f64::NAN)
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let integer_digits =
+            if self.integer_digits.is_empty() { "0" } else { &self.integer_digits };
+        let fraction_digits =
+            if self.fraction_digits.is_empty() { "0" } else { &self.fraction_digits };
+        let sign = if self.negative { "-" } else { "" };
+        write!(f, "{}{}.{}", sign, integer_digits, fraction_digits)
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_year(f, self.year)?;
+        write!(f, "-{:02}-{:02}", self.month, self.day)?;
+        write_timezone(f, self.timezone)
+    }
+}
+
+impl Display for DateTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_year(f, self.year)?;
+        write!(f, "-{:02}-{:02}T{:02}:{:02}:{:02}", self.month, self.day, self.hour, self.minute,
+               self.second)?;
+        write_nanoseconds(f, self.nanosecond)?;
+        write_timezone(f, self.timezone)
+    }
+}
+
+impl Display for Duration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.months == 0 && self.seconds == 0 && self.nanoseconds == 0 {
+            return match self.kind {
+                DurationKind::YearMonthDuration => { write!(f, "P0M") }
+                _ => { write!(f, "PT0S") }
+            };
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "P")?;
+        let (years, months) = (self.months / 12, self.months % 12);
+        let (days, rest) = (self.seconds / 86400, self.seconds % 86400);
+        let (hours, minutes, seconds) = (rest / 3600, rest % 3600 / 60, rest % 60);
+        if years > 0 {
+            write!(f, "{}Y", years)?;
+        }
+        if months > 0 {
+            write!(f, "{}M", months)?;
+        }
+        if days > 0 {
+            write!(f, "{}D", days)?;
+        }
+        if hours > 0 || minutes > 0 || seconds > 0 || self.nanoseconds > 0 {
+            write!(f, "T")?;
+            if hours > 0 {
+                write!(f, "{}H", hours)?;
+            }
+            if minutes > 0 {
+                write!(f, "{}M", minutes)?;
+            }
+            if seconds > 0 || self.nanoseconds > 0 {
+                write!(f, "{}", seconds)?;
+                write_nanoseconds(f, self.nanoseconds)?;
+                write!(f, "S")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The bounds of the integer datatype, where `None` means unbounded.
+fn integer_range(datatype: &Iri) -> Option<(Option<i128>, Option<i128>)> {
+    let range =
+        if datatype == xsd::INTEGER {
+            (None, None)
+        } else if datatype == xsd::LONG {
+            (Some(i64::MIN as i128), Some(i64::MAX as i128))
+        } else if datatype == xsd::INT {
+            (Some(i32::MIN as i128), Some(i32::MAX as i128))
+        } else if datatype == xsd::SHORT {
+            (Some(i16::MIN as i128), Some(i16::MAX as i128))
+        } else if datatype == xsd::BYTE {
+            (Some(i8::MIN as i128), Some(i8::MAX as i128))
+        } else if datatype == xsd::NON_NEGATIVE_INTEGER {
+            (Some(0), None)
+        } else if datatype == xsd::POSITIVE_INTEGER {
+            (Some(1), None)
+        } else if datatype == xsd::NON_POSITIVE_INTEGER {
+            (None, Some(0))
+        } else if datatype == xsd::NEGATIVE_INTEGER {
+            (None, Some(-1))
+        } else if datatype == xsd::UNSIGNED_LONG {
+            (Some(0), Some(u64::MAX as i128))
+        } else if datatype == xsd::UNSIGNED_INT {
+            (Some(0), Some(u32::MAX as i128))
+        } else if datatype == xsd::UNSIGNED_SHORT {
+            (Some(0), Some(u16::MAX as i128))
+        } else if datatype == xsd::UNSIGNED_BYTE {
+            (Some(0), Some(u8::MAX as i128))
+        } else {
+            return None;
+        };
+    Some(range)
+}
+
+fn split_sign(string: &str) -> (bool, &str) {
+    match string.strip_prefix('-') {
+        Some(rest) => { (true, rest) }
+        None => { (false, string.strip_prefix('+').unwrap_or(string)) }
+    }
+}
+
+fn is_digits(string: &str) -> bool {
+    !string.is_empty() && string.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn parse_integer(string: &str) -> Option<Integer> {
+    let (negative, digits) = split_sign(string);
+    if !is_digits(digits) {
+        return None;
+    }
+    let digits = digits.trim_start_matches('0');
+    let integer =
+        if digits.is_empty() {
+            Integer { negative: false, digits: "0".to_string() }
+        } else {
+            Integer { negative, digits: digits.to_string() }
+        };
+    Some(integer)
+}
+
+fn parse_decimal(string: &str) -> Option<Decimal> {
+    let (negative, unsigned) = split_sign(string);
+    let (integer_digits, fraction_digits) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let is_valid =
+        (is_digits(integer_digits) || is_digits(fraction_digits))
+            && (integer_digits.is_empty() || is_digits(integer_digits))
+            && (fraction_digits.is_empty() || is_digits(fraction_digits));
+    if !is_valid {
+        return None;
+    }
+    let integer_digits = integer_digits.trim_start_matches('0').to_string();
+    let fraction_digits = fraction_digits.trim_end_matches('0').to_string();
+    let negative = negative && !(integer_digits.is_empty() && fraction_digits.is_empty());
+    Some(Decimal { negative, integer_digits, fraction_digits })
+}
+
+fn parse_double(string: &str) -> Option<f64> {
+    match string {
+        "INF" | "+INF" => { return Some(f64::INFINITY); }
+        "-INF" => { return Some(f64::NEG_INFINITY); }
+        "NaN" => { return Some(f64::NAN); }
+        _ => {}
+    }
+    let mantissa = string.split(['e', 'E']).next().unwrap_or(string);
+    let exponent = &string[mantissa.len()..];
+    let is_valid =
+        parse_decimal(mantissa).is_some()
+            && (exponent.is_empty() || parse_integer(&exponent[1..]).is_some());
+    if is_valid { string.parse().ok() } else { None }
+}
+
+fn canonical_double<X: std::fmt::UpperExp + Into<f64> + Copy>(x: X) -> String {
+    let float: f64 = x.into();
+    if float.is_nan() {
+        "NaN".to_string()
+    } else if float.is_infinite() {
+        if float > 0.0 { "INF".to_string() } else { "-INF".to_string() }
+    } else {
+        let string = format!("{:E}", x);
+        match string.split_once('E') {
+            Some((mantissa, exponent)) if !mantissa.contains('.') => {
+                format!("{}.0E{}", mantissa, exponent)
+            }
+            _ => { string }
+        }
+    }
+}
+
+fn take_digits<'a>(string: &mut &'a str, n: usize) -> Option<&'a str> {
+    let digits = string.get(..n).filter(|digits| is_digits(digits))?;
+    *string = &string[n..];
+    Some(digits)
+}
+
+fn take_char(string: &mut &str, c: char) -> Option<()> {
+    *string = string.strip_prefix(c)?;
+    Some(())
+}
+
+fn parse_year(string: &mut &str) -> Option<i64> {
+    let negative = string.starts_with('-');
+    if negative {
+        *string = &string[1..];
+    }
+    let n_digits = string.bytes().take_while(|b| b.is_ascii_digit()).count();
+    if n_digits < 4 || (n_digits > 4 && string.starts_with('0')) {
+        return None;
+    }
+    let year: i64 = take_digits(string, n_digits)?.parse().ok()?;
+    Some(if negative { -year } else { year })
+}
+
+fn days_in_month(year: i64, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => { 29 }
+        2 => { 28 }
+        4 | 6 | 9 | 11 => { 30 }
+        _ => { 31 }
+    }
+}
+
+fn parse_year_month_day(string: &mut &str) -> Option<(i64, u8, u8)> {
+    let year = parse_year(string)?;
+    take_char(string, '-')?;
+    let month: u8 = take_digits(string, 2)?.parse().ok()?;
+    take_char(string, '-')?;
+    let day: u8 = take_digits(string, 2)?.parse().ok()?;
+    let is_valid = (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month);
+    if is_valid { Some((year, month, day)) } else { None }
+}
+
+fn parse_timezone(string: &str) -> Option<Option<i16>> {
+    if string.is_empty() {
+        return Some(None);
+    }
+    if string == "Z" {
+        return Some(Some(0));
+    }
+    let (negative, mut rest) = split_sign(string);
+    if rest.len() == string.len() {
+        return None;
+    }
+    let hours: i16 = take_digits(&mut rest, 2)?.parse().ok()?;
+    take_char(&mut rest, ':')?;
+    let minutes: i16 = take_digits(&mut rest, 2)?.parse().ok()?;
+    let is_valid = rest.is_empty() && minutes < 60 && (hours < 14 || (hours == 14 && minutes == 0));
+    let offset = hours * 60 + minutes;
+    if is_valid { Some(Some(if negative { -offset } else { offset })) } else { None }
+}
+
+fn parse_date(string: &str) -> Option<Date> {
+    let mut rest = string;
+    let (year, month, day) = parse_year_month_day(&mut rest)?;
+    let timezone = parse_timezone(rest)?;
+    Some(Date { year, month, day, timezone })
+}
+
+fn parse_date_time(string: &str) -> Option<DateTime> {
+    let mut rest = string;
+    let (mut year, mut month, mut day) = parse_year_month_day(&mut rest)?;
+    take_char(&mut rest, 'T')?;
+    let mut hour: u8 = take_digits(&mut rest, 2)?.parse().ok()?;
+    take_char(&mut rest, ':')?;
+    let minute: u8 = take_digits(&mut rest, 2)?.parse().ok()?;
+    take_char(&mut rest, ':')?;
+    let second: u8 = take_digits(&mut rest, 2)?.parse().ok()?;
+    let nanosecond =
+        if rest.starts_with('.') {
+            rest = &rest[1..];
+            let n_digits = rest.bytes().take_while(|b| b.is_ascii_digit()).count();
+            parse_nanoseconds(take_digits(&mut rest, n_digits)?)?
+        } else {
+            0
+        };
+    let timezone = parse_timezone(rest)?;
+    if minute > 59 || second > 59 || hour > 24
+        || (hour == 24 && (minute > 0 || second > 0 || nanosecond > 0)) {
+        return None;
+    }
+    if hour == 24 {
+        hour = 0;
+        day += 1;
+        if day > days_in_month(year, month) {
+            day = 1;
+            month += 1;
+            if month > 12 {
+                month = 1;
+                year += 1;
+            }
+        }
+    }
+    Some(DateTime { year, month, day, hour, minute, second, nanosecond, timezone })
+}
+
+/// Digits beyond nanosecond precision must be zero.
+fn parse_nanoseconds(digits: &str) -> Option<u32> {
+    let (significant, rest) = digits.split_at(digits.len().min(9));
+    if rest.bytes().any(|b| b != b'0') {
+        return None;
+    }
+    format!("{:0<9}", significant).parse().ok()
+}
+
+fn parse_duration(string: &str, kind: DurationKind) -> Option<Duration> {
+    let allows_months = kind != DurationKind::DayTimeDuration;
+    let allows_seconds = kind != DurationKind::YearMonthDuration;
+    let (negative, rest) =
+        match string.strip_prefix('-') {
+            Some(rest) => { (true, rest) }
+            None => { (false, string) }
+        };
+    let rest = rest.strip_prefix('P')?;
+    let (date_part, time_part) =
+        match rest.split_once('T') {
+            Some((date_part, time_part)) => {
+                if time_part.is_empty() {
+                    return None;
+                }
+                (date_part, Some(time_part))
+            }
+            None => { (rest, None) }
+        };
+    if date_part.is_empty() && time_part.is_none() {
+        return None;
+    }
+    let mut months: u64 = 0;
+    let mut seconds: u64 = 0;
+    let mut nanoseconds: u32 = 0;
+    for (designator, number) in duration_components(date_part, "YMD")? {
+        let number: u64 = number.parse().ok()?;
+        match designator {
+            'Y' if allows_months => { months = months.checked_add(number.checked_mul(12)?)?; }
+            'M' if allows_months => { months = months.checked_add(number)?; }
+            'D' if allows_seconds => { seconds = seconds.checked_add(number.checked_mul(86400)?)?; }
+            _ => { return None; }
+        }
+    }
+    if let Some(time_part) = time_part {
+        if !allows_seconds {
+            return None;
+        }
+        for (designator, number) in duration_components(time_part, "HMS")? {
+            let (whole, fraction) =
+                match number.split_once('.') {
+                    Some((whole, fraction)) if designator == 'S' => {
+                        (whole, Some(fraction))
+                    }
+                    Some(_) => { return None; }
+                    None => { (number, None) }
+                };
+            let whole: u64 = whole.parse().ok()?;
+            let factor =
+                match designator {
+                    'H' => { 3600 }
+                    'M' => { 60 }
+                    _ => { 1 }
+                };
+            seconds = seconds.checked_add(whole.checked_mul(factor)?)?;
+            if let Some(fraction) = fraction {
+                if !is_digits(fraction) {
+                    return None;
+                }
+                nanoseconds = parse_nanoseconds(fraction)?;
+            }
+        }
+    }
+    Some(Duration { kind, negative, months, seconds, nanoseconds })
+}
+
+/// Splits e.g. "1Y2D" into ('Y', "1"), ('D', "2"), requiring the designators in order.
+fn duration_components<'a>(string: &'a str, designators: &str)
+                           -> Option<Vec<(char, &'a str)>> {
+    let mut components: Vec<(char, &str)> = Vec::new();
+    let mut remaining_designators = designators.chars();
+    let mut rest = string;
+    while !rest.is_empty() {
+        let end = rest.find(|c: char| c.is_ascii_alphabetic())?;
+        let designator = rest[end..].chars().next()?;
+        if !remaining_designators.any(|c| c == designator) {
+            return None;
+        }
+        let number = &rest[..end];
+        let (whole, _) = number.split_once('.').unwrap_or((number, ""));
+        if !is_digits(whole) {
+            return None;
+        }
+        components.push((designator, number));
+        rest = &rest[end + 1..];
+    }
+    Some(components)
+}
+
+fn write_year(f: &mut Formatter<'_>, year: i64) -> std::fmt::Result {
+    if year < 0 {
+        write!(f, "-{:04}", -year)
+    } else {
+        write!(f, "{:04}", year)
+    }
+}
+
+fn write_nanoseconds(f: &mut Formatter<'_>, nanoseconds: u32) -> std::fmt::Result {
+    if nanoseconds > 0 {
+        let digits = format!("{:09}", nanoseconds);
+        write!(f, ".{}", digits.trim_end_matches('0'))?;
+    }
+    Ok(())
+}
+
+fn write_timezone(f: &mut Formatter<'_>, timezone: Option<i16>) -> std::fmt::Result {
+    match timezone {
+        None => { Ok(()) }
+        Some(0) => { write!(f, "Z") }
+        Some(offset) => {
+            let sign = if offset < 0 { '-' } else { '+' };
+            write!(f, "{}{:02}:{:02}", sign, offset.abs() / 60, offset.abs() % 60)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::literal::{Literal, LiteralTag};
+    use crate::model::value::Value;
+    use crate::vocabs::xsd;
+
+    fn canonical(lexical: &str, datatype: &crate::model::iri::Iri) -> String {
+        Value::parse(lexical, datatype).unwrap().unwrap().to_string()
+    }
+
+    #[test]
+    fn canonicalizes() {
+        assert_eq!(canonical("+007", xsd::INTEGER), "7");
+        assert_eq!(canonical("-0", xsd::INTEGER), "0");
+        assert_eq!(canonical(" 255 ", xsd::UNSIGNED_BYTE), "255");
+        let digits = "12345678901234567890123456789012345678901234567890";
+        assert_eq!(canonical(&format!("-00{}", digits), xsd::INTEGER), format!("-{}", digits));
+        assert_eq!(canonical(digits, xsd::POSITIVE_INTEGER), digits);
+        assert_eq!(canonical("+01.500", xsd::DECIMAL), "1.5");
+        assert_eq!(canonical("-.0", xsd::DECIMAL), "0.0");
+        assert_eq!(canonical("3", xsd::DECIMAL), "3.0");
+        assert_eq!(canonical("100", xsd::DOUBLE), "1.0E2");
+        assert_eq!(canonical("-1.25e-3", xsd::DOUBLE), "-1.25E-3");
+        assert_eq!(canonical("INF", xsd::FLOAT), "INF");
+        assert_eq!(canonical("1", xsd::BOOLEAN), "true");
+        assert_eq!(canonical("2024-02-29+00:00", xsd::DATE), "2024-02-29Z");
+        assert_eq!(canonical("1999-12-31T24:00:00.000-05:30", xsd::DATE_TIME),
+                   "2000-01-01T00:00:00-05:30");
+        assert_eq!(canonical("2001-01-01T12:30:05.250Z", xsd::DATE_TIME),
+                   "2001-01-01T12:30:05.25Z");
+        assert_eq!(canonical("P1Y14M2DT25H0M1.50S", xsd::DURATION), "P2Y2M3DT1H1.5S");
+        assert_eq!(canonical("-PT0S", xsd::DURATION), "PT0S");
+        assert_eq!(canonical("P0Y", xsd::YEAR_MONTH_DURATION), "P0M");
+        assert_eq!(canonical("-P0D", xsd::DAY_TIME_DURATION), "PT0S");
+        let zero = Literal::new("P0Y".to_string(),
+                                LiteralTag::Type(xsd::YEAR_MONTH_DURATION.clone()));
+        assert!(zero.canonical().unwrap().is_valid());
+        assert_eq!(canonical(" http://example.org/a ", xsd::ANY_URI), "http://example.org/a");
+    }
+
+    #[test]
+    fn rejects_invalid_lexical_forms() {
+        let invalid = [
+            ("1.5", xsd::INTEGER), ("", xsd::INTEGER), ("256", xsd::UNSIGNED_BYTE),
+            ("0", xsd::POSITIVE_INTEGER), ("-1", xsd::NON_NEGATIVE_INTEGER), (".", xsd::DECIMAL),
+            ("-12345678901234567890123456789012345678901234567890", xsd::NON_NEGATIVE_INTEGER),
+            ("12345678901234567890123456789012345678901234567890", xsd::LONG),
+            ("1e", xsd::DOUBLE), ("inf", xsd::DOUBLE), ("yes", xsd::BOOLEAN),
+            ("2023-02-29", xsd::DATE), ("99-01-01", xsd::DATE),
+            ("2023-01-01T24:00:01", xsd::DATE_TIME),
+            ("2023-01-01T10:00:00+15:00", xsd::DATE_TIME), ("P", xsd::DURATION),
+            ("P1DT", xsd::DURATION), ("P1D1Y", xsd::DURATION), ("P1D", xsd::YEAR_MONTH_DURATION),
+            ("P1.5Y", xsd::DURATION),
+        ];
+        for (lexical, datatype) in invalid {
+            assert!(Value::parse(lexical, datatype).is_err(), "{} {}", lexical, datatype);
+        }
+        assert!(Value::parse("x", xsd::STRING).unwrap().is_none());
+    }
+
+    #[test]
+    fn converts_literals() {
+        let literal = Literal::from(42i64);
+        assert_eq!(literal.string, "42");
+        assert!(literal.literal_tag == LiteralTag::Type(xsd::INTEGER.clone()));
+        assert_eq!(i64::try_from(&literal).unwrap(), 42);
+        assert_eq!(u64::try_from(&Literal::from(7u64)).unwrap(), 7);
+        assert!(Literal::from(5i32) == Literal::from(5i64));
+        assert!(Literal::from(5u32) == Literal::from(5u64));
+        assert!(u64::try_from(&Literal::from(-7i64)).is_err());
+        assert!(bool::try_from(&Literal::from(true)).unwrap());
+        assert_eq!(f64::try_from(&Literal::from(1.5f32)).unwrap(), 1.5);
+        assert!(i64::try_from(&Literal::from("42".to_string())).is_err());
+        let literal = Literal::new("+01.50".to_string(), LiteralTag::Type(xsd::DECIMAL.clone()));
+        assert_eq!(literal.canonical().unwrap().string, "1.5");
+        assert!(literal.value().unwrap() == Value::parse("1.5", xsd::DECIMAL).unwrap());
+        let literal = Literal::new("abc".to_string(), LiteralTag::Type(xsd::INT.clone()));
+        assert!(!literal.is_valid());
+    }
+}
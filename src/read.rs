@@ -1 +1,8 @@
-pub mod xml;
\ No newline at end of file
+pub mod xml;
+pub mod turtle;
+pub mod ntriples;
+pub mod nquads;
+pub mod trig;
+pub mod jsonld;
+pub mod sink;
+mod blank_nodes;
\ No newline at end of file
@@ -0,0 +1,39 @@
+use crate::model::node::BlankNode;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Hands out blank nodes for a document, keeping the document's own labels where possible
+/// and making sure generated labels never collide with them.
+pub(crate) struct BlankNodes {
+    prefix: &'static str,
+    labeled: BTreeMap<String, BlankNode>,
+    used: BTreeSet<String>,
+    n_generated: usize,
+}
+
+impl BlankNodes {
+    pub(crate) fn new(prefix: &'static str) -> BlankNodes {
+        BlankNodes { prefix, labeled: BTreeMap::new(), used: BTreeSet::new(), n_generated: 0 }
+    }
+    pub(crate) fn fresh(&mut self) -> BlankNode {
+        loop {
+            let id = format!("{}{}", self.prefix, self.n_generated);
+            self.n_generated += 1;
+            if self.used.insert(id.clone()) {
+                return BlankNode::from(id);
+            }
+        }
+    }
+    pub(crate) fn labeled(&mut self, label: String) -> BlankNode {
+        if let Some(blank_node) = self.labeled.get(&label) {
+            return blank_node.clone();
+        }
+        let blank_node =
+            if self.used.insert(label.clone()) {
+                BlankNode::from(label.clone())
+            } else {
+                self.fresh()
+            };
+        self.labeled.insert(label, blank_node.clone());
+        blank_node
+    }
+}
@@ -0,0 +1,1327 @@
+use crate::error::PenyuError;
+use crate::model::dataset::MemoryDataset;
+use crate::model::graph::MemoryGraph;
+use crate::model::iri::Iri;
+use crate::model::literal::{Literal, LiteralTag};
+use crate::model::node::{Entity, Node};
+use crate::read::blank_nodes::BlankNodes;
+use crate::syntax;
+use crate::vocabs::{rdf, xsd};
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Read;
+use std::rc::Rc;
+
+/// Fetches the documents that remote contexts refer to.
+pub trait DocumentLoader {
+    fn load_document(&self, iri: &Iri) -> Result<Value, PenyuError>;
+}
+
+/// Refuses to load anything, so any remote context is an error.
+pub struct NoDocumentLoader;
+
+impl DocumentLoader for NoDocumentLoader {
+    fn load_document(&self, iri: &Iri) -> Result<Value, PenyuError> {
+        Err(PenyuError::from(format!("No document loader to load remote context <{}>", iri)))
+    }
+}
+
+/// Serves documents from memory, keyed by IRI.
+impl DocumentLoader for BTreeMap<String, Value> {
+    fn load_document(&self, iri: &Iri) -> Result<Value, PenyuError> {
+        self.get(&iri.to_string()).cloned().ok_or_else(|| {
+            PenyuError::from(format!("Unknown remote context <{}>", iri))
+        })
+    }
+}
+
+pub fn read<R: Read>(read: &mut R) -> Result<MemoryGraph, PenyuError> {
+    read_with_loader(read, &NoDocumentLoader)
+}
+
+/// Reads the default graph, ignoring any named graphs.
+pub fn read_with_loader<R: Read, L: DocumentLoader>(read: &mut R, loader: &L)
+                                                    -> Result<MemoryGraph, PenyuError> {
+    Ok(read_dataset(read, loader)?.into_default_graph())
+}
+
+/// Reads all graphs. Prefix definitions and `@base` of the top-level contexts become the
+/// dataset's prefixes and base.
+pub fn read_dataset<R: Read, L: DocumentLoader>(read: &mut R, loader: &L)
+                                                -> Result<MemoryDataset, PenyuError> {
+    let document: Value = serde_json::from_reader(read)?;
+    let processor = Processor::new(loader);
+    let expanded = processor.expand_document(&document)?;
+    let mut builder = Builder { dataset: MemoryDataset::new(), blank_nodes: BlankNodes::new("b") };
+    for item in as_array(&expanded) {
+        if let Value::Object(node) = item {
+            builder.add_node(node, &None)?;
+        }
+    }
+    let mut dataset = builder.dataset;
+    for top in as_array(&document) {
+        if let Some(local) = top.get("@context") {
+            let context =
+                processor.process_context(&Rc::default(), local, &mut Vec::new(), false, true)?;
+            let context = Rc::unwrap_or_clone(context);
+            if let Some(base) = context.base {
+                dataset.set_base_ns(base);
+            }
+            for (term, definition) in context.terms {
+                if let Some(definition) = definition {
+                    if definition.is_prefix && !definition.is_reverse && !term.contains(':')
+                        && syntax::is_absolute_iri(&definition.iri) {
+                        dataset.add_prefix(term, Iri::from(definition.iri));
+                    }
+                }
+            }
+        }
+    }
+    Ok(dataset)
+}
+
+/// Expands a JSON-LD document, following the JSON-LD 1.1 expansion algorithm.
+pub fn expand<L: DocumentLoader>(document: &Value, loader: &L) -> Result<Value, PenyuError> {
+    Processor::new(loader).expand_document(document)
+}
+
+#[derive(Clone, Default)]
+struct Context {
+    base: Option<Iri>,
+    vocab: Option<String>,
+    language: Option<String>,
+    /// Terms explicitly mapped to null are kept as `None`.
+    terms: BTreeMap<String, Option<Term>>,
+    /// The context that nested nodes return to, set by a context that does not propagate.
+    previous: Option<Rc<Context>>,
+}
+
+#[derive(Clone, PartialEq)]
+struct Term {
+    iri: String,
+    is_reverse: bool,
+    is_prefix: bool,
+    type_mapping: Option<String>,
+    language: Option<Option<String>>,
+    containers: BTreeSet<String>,
+    context: Option<Value>,
+    is_protected: bool,
+}
+
+/// The term definitions of a local context, and how they may replace existing ones.
+struct LocalContext<'a> {
+    definitions: &'a Map<String, Value>,
+    /// The default for terms that don't set `@protected` themselves.
+    is_protected: bool,
+    /// Whether protected terms may be redefined, as property-scoped contexts may.
+    override_protected: bool,
+}
+
+impl Context {
+    fn term(&self, key: &str) -> Option<&Term> {
+        self.terms.get(key).and_then(Option::as_ref)
+    }
+    fn expand_iri(&self, value: &str, document_relative: bool, vocab: bool) -> Option<String> {
+        if is_keyword(value) {
+            return Some(value.to_string());
+        }
+        if looks_like_keyword(value) {
+            return None;
+        }
+        if vocab {
+            if let Some(term) = self.terms.get(value) {
+                return term.as_ref().map(|term| term.iri.clone());
+            }
+        }
+        if let Some((prefix, suffix)) = value.split_once(':').filter(|(prefix, _)| {
+            !prefix.is_empty()
+        }) {
+            if prefix == "_" || suffix.starts_with("//") {
+                return Some(value.to_string());
+            }
+            if let Some(term) = self.term(prefix).filter(|term| term.is_prefix) {
+                return Some(format!("{}{}", term.iri, suffix));
+            }
+            if syntax::is_absolute_iri(value) {
+                return Some(value.to_string());
+            }
+        }
+        if vocab {
+            if let Some(vocab) = &self.vocab {
+                return Some(format!("{}{}", vocab, value));
+            }
+        }
+        if document_relative {
+            if let Some(base) = &self.base {
+                return Some(base.resolve(value).to_string());
+            }
+        }
+        Some(value.to_string())
+    }
+}
+
+struct Processor<'a, L: DocumentLoader> {
+    loader: &'a L,
+    /// Documents loaded so far, so that each is loaded only once.
+    documents: RefCell<BTreeMap<String, Rc<Value>>>,
+}
+
+const CONTAINERS: [&str; 7] = ["@list", "@set", "@language", "@index", "@id", "@type", "@graph"];
+
+impl<'a, L: DocumentLoader> Processor<'a, L> {
+    fn new(loader: &'a L) -> Processor<'a, L> {
+        Processor { loader, documents: RefCell::new(BTreeMap::new()) }
+    }
+    fn load_document(&self, iri: &Iri) -> Result<Rc<Value>, PenyuError> {
+        let key = iri.to_string();
+        if let Some(document) = self.documents.borrow().get(&key) {
+            return Ok(Rc::clone(document));
+        }
+        let document = Rc::new(self.loader.load_document(iri)?);
+        self.documents.borrow_mut().insert(key, Rc::clone(&document));
+        Ok(document)
+    }
+    /// Processes a local context. A context that does not propagate, like a type-scoped
+    /// context, only applies to the node it is used on, not to nested nodes.
+    fn process_context(&self, active: &Rc<Context>, local: &Value, remote: &mut Vec<String>,
+                       override_protected: bool, propagate: bool)
+                       -> Result<Rc<Context>, PenyuError> {
+        let mut result = Context::clone(active);
+        let propagate =
+            match local.get("@propagate") {
+                None => { propagate }
+                Some(Value::Bool(propagate)) => { *propagate }
+                Some(value) => {
+                    return Err(PenyuError::from(format!("Invalid @propagate {}", value)));
+                }
+            };
+        if !propagate && result.previous.is_none() {
+            result.previous = Some(Rc::clone(active));
+        }
+        let result = self.apply_context(result, local, remote, override_protected, propagate)?;
+        Ok(Rc::new(result))
+    }
+    fn apply_context(&self, mut result: Context, local: &Value, remote: &mut Vec<String>,
+                     override_protected: bool, propagate: bool) -> Result<Context, PenyuError> {
+        for local in as_array(local) {
+            match local {
+                Value::Null => {
+                    let has_protected =
+                        result.terms.values().flatten().any(|term| term.is_protected);
+                    if !override_protected && has_protected {
+                        return Err(PenyuError::from(
+                            "Invalid context nullification with protected terms"
+                        ));
+                    }
+                    let previous = if propagate { None } else { Some(Rc::new(result.clone())) };
+                    result = Context { base: result.base, previous, ..Context::default() };
+                }
+                Value::String(reference) => {
+                    let iri =
+                        match &result.base {
+                            Some(base) => { base.resolve(reference) }
+                            None => { Iri::from(reference.clone()) }
+                        };
+                    let key = iri.to_string();
+                    if remote.contains(&key) {
+                        return Err(PenyuError::from(format!(
+                            "Recursive inclusion of remote context <{}>", key
+                        )));
+                    }
+                    let document = self.load_document(&iri)?;
+                    let context = document.get("@context").ok_or_else(|| {
+                        PenyuError::from(format!("Remote context <{}> has no @context", key))
+                    })?;
+                    remote.push(key);
+                    result = self.apply_context(result, context, remote, override_protected,
+                                                true)?;
+                    remote.pop();
+                }
+                Value::Object(definitions) => {
+                    result =
+                        self.process_definitions(result, definitions, remote, override_protected)?;
+                }
+                _ => {
+                    return Err(PenyuError::from(format!("Invalid local context {}", local)));
+                }
+            }
+        }
+        Ok(result)
+    }
+    fn process_definitions(&self, mut result: Context, definitions: &Map<String, Value>,
+                           remote: &mut Vec<String>, override_protected: bool)
+                           -> Result<Context, PenyuError> {
+        let mut definitions = definitions.clone();
+        if let Some(import) = definitions.remove("@import") {
+            let iri =
+                match (import.as_str(), &result.base) {
+                    (Some(import), Some(base)) => { base.resolve(import) }
+                    (Some(import), None) => { Iri::from(import.to_string()) }
+                    (None, _) => { return Err(PenyuError::from("Invalid @import value")); }
+                };
+            let document = self.load_document(&iri)?;
+            match document.get("@context") {
+                Some(Value::Object(imported)) => {
+                    let mut merged = imported.clone();
+                    merged.extend(definitions);
+                    definitions = merged;
+                }
+                _ => {
+                    return Err(PenyuError::from(format!(
+                        "Imported context <{}> is not a single context", iri
+                    )));
+                }
+            }
+        }
+        if let Some(version) = definitions.get("@version") {
+            if version.as_f64() != Some(1.1) {
+                return Err(PenyuError::from(format!("Invalid @version {}", version)));
+            }
+        }
+        match definitions.get("@base") {
+            Some(Value::Null) => { result.base = None; }
+            Some(Value::String(base)) => {
+                result.base =
+                    match &result.base {
+                        Some(active_base) => { Some(active_base.resolve(base)) }
+                        None => { Some(Iri::from(base.clone())) }
+                    };
+            }
+            Some(base) => { return Err(PenyuError::from(format!("Invalid @base {}", base))); }
+            None => {}
+        }
+        match definitions.get("@vocab") {
+            Some(Value::Null) => { result.vocab = None; }
+            Some(Value::String(vocab)) => { result.vocab = result.expand_iri(vocab, true, true); }
+            Some(vocab) => { return Err(PenyuError::from(format!("Invalid @vocab {}", vocab))); }
+            None => {}
+        }
+        match definitions.get("@language") {
+            Some(Value::Null) => { result.language = None; }
+            Some(Value::String(language)) => { result.language = Some(language.clone()); }
+            Some(language) => {
+                return Err(PenyuError::from(format!("Invalid @language {}", language)));
+            }
+            None => {}
+        }
+        let is_protected =
+            match definitions.get("@protected") {
+                None => { false }
+                Some(Value::Bool(is_protected)) => { *is_protected }
+                Some(value) => {
+                    return Err(PenyuError::from(format!("Invalid @protected {}", value)));
+                }
+            };
+        let local = LocalContext { definitions: &definitions, is_protected, override_protected };
+        let mut defined: BTreeMap<String, bool> = BTreeMap::new();
+        for term in definitions.keys() {
+            if !matches!(term.as_str(), "@base" | "@version" | "@vocab" | "@language"
+                | "@propagate" | "@protected" | "@direction") {
+                self.define_term(&mut result, &local, term, &mut defined, remote)?;
+            }
+        }
+        Ok(result)
+    }
+    fn define_term(&self, context: &mut Context, local: &LocalContext, term: &str,
+                   defined: &mut BTreeMap<String, bool>, remote: &mut Vec<String>)
+                   -> Result<(), PenyuError> {
+        let definitions = local.definitions;
+        match defined.get(term) {
+            Some(true) => { return Ok(()); }
+            Some(false) => {
+                return Err(PenyuError::from(format!("Cyclic IRI mapping for term {}", term)));
+            }
+            None => {}
+        }
+        if term.is_empty() || is_keyword(term) {
+            return Err(PenyuError::from(format!("Invalid term definition for '{}'", term)));
+        }
+        defined.insert(term.to_string(), false);
+        let previous = context.term(term).cloned();
+        let value = &definitions[term];
+        let (definition, is_simple) =
+            match value {
+                Value::Null => {
+                    set_term(context, local, term, previous, None)?;
+                    defined.insert(term.to_string(), true);
+                    return Ok(());
+                }
+                Value::String(iri) => {
+                    let mut definition = Map::new();
+                    definition.insert("@id".to_string(), Value::String(iri.clone()));
+                    (definition, true)
+                }
+                Value::Object(definition) => { (definition.clone(), false) }
+                _ => {
+                    return Err(PenyuError::from(format!(
+                        "Invalid term definition for '{}': {}", term, value
+                    )));
+                }
+            };
+        let type_mapping =
+            match definition.get("@type") {
+                Some(Value::String(type_mapping)) => {
+                    let type_mapping =
+                        self.expand_iri_defining(context, local, type_mapping, defined,
+                                                 remote)?.filter(|type_mapping| {
+                            matches!(type_mapping.as_str(), "@id" | "@vocab" | "@json" | "@none")
+                                || syntax::is_absolute_iri(type_mapping)
+                        }).ok_or_else(|| {
+                            PenyuError::from(format!("Invalid type mapping for term {}", term))
+                        })?;
+                    Some(type_mapping)
+                }
+                Some(_) => {
+                    return Err(PenyuError::from(format!("Invalid type mapping for {}", term)));
+                }
+                None => { None }
+            };
+        let mut is_reverse = false;
+        let iri =
+            if let Some(reverse) = definition.get("@reverse") {
+                is_reverse = true;
+                let invalid = || {
+                    PenyuError::from(format!("Invalid reverse property for term {}", term))
+                };
+                let reverse = reverse.as_str().ok_or_else(invalid)?;
+                self.expand_iri_defining(context, local, reverse, defined, remote)?
+                    .filter(|iri| iri.contains(':')).ok_or_else(invalid)?
+            } else if let Some(id) = definition.get("@id") {
+                match id {
+                    Value::Null => {
+                        set_term(context, local, term, previous, None)?;
+                        defined.insert(term.to_string(), true);
+                        return Ok(());
+                    }
+                    Value::String(id) => {
+                        self.expand_iri_defining(context, local, id, defined, remote)?
+                            .filter(|iri| {
+                            (is_keyword(iri) && iri != "@context") || iri.contains(':')
+                        }).ok_or_else(|| {
+                            PenyuError::from(format!("Invalid IRI mapping for term {}", term))
+                        })?
+                    }
+                    _ => {
+                        return Err(PenyuError::from(format!("Invalid IRI mapping for {}", term)));
+                    }
+                }
+            } else if let Some((prefix, suffix)) =
+                term.split_once(':').filter(|(prefix, _)| !prefix.is_empty()) {
+                if definitions.contains_key(prefix) {
+                    self.define_term(context, local, prefix, defined, remote)?;
+                }
+                match context.term(prefix) {
+                    Some(prefix_term) => { format!("{}{}", prefix_term.iri, suffix) }
+                    None => { term.to_string() }
+                }
+            } else if let Some(vocab) = &context.vocab {
+                format!("{}{}", vocab, term)
+            } else {
+                return Err(PenyuError::from(format!("No IRI mapping for term {}", term)));
+            };
+        let containers: BTreeSet<String> =
+            match definition.get("@container") {
+                None | Some(Value::Null) => { BTreeSet::new() }
+                Some(container) => {
+                    as_array(container).iter().map(|container| {
+                        container.as_str().filter(|container| CONTAINERS.contains(container))
+                            .map(str::to_string).ok_or_else(|| {
+                                PenyuError::from(format!("Invalid container for term {}", term))
+                            })
+                    }).collect::<Result<_, _>>()?
+                }
+            };
+        let language =
+            match definition.get("@language") {
+                None => { None }
+                Some(Value::Null) => { Some(None) }
+                Some(Value::String(language)) => { Some(Some(language.clone())) }
+                Some(_) => {
+                    return Err(PenyuError::from(format!("Invalid language for term {}", term)));
+                }
+            };
+        if let Some(nest) = definition.get("@nest") {
+            if !nest.as_str().is_some_and(|nest| nest == "@nest" || !is_keyword(nest)) {
+                return Err(PenyuError::from(format!("Invalid @nest for term {}", term)));
+            }
+        }
+        let is_protected =
+            match definition.get("@protected") {
+                None => { local.is_protected }
+                Some(Value::Bool(is_protected)) => { *is_protected }
+                Some(_) => {
+                    return Err(PenyuError::from(format!("Invalid @protected for term {}", term)));
+                }
+            };
+        let is_prefix =
+            match definition.get("@prefix") {
+                Some(Value::Bool(is_prefix)) => { *is_prefix }
+                Some(_) => {
+                    return Err(PenyuError::from(format!("Invalid @prefix for term {}", term)));
+                }
+                None => {
+                    is_simple && !term.contains(':') && !term.contains('/')
+                        && iri.ends_with([':', '/', '?', '#', '[', ']', '@'])
+                }
+            };
+        let term_definition = Term {
+            iri,
+            is_reverse,
+            is_prefix,
+            type_mapping,
+            language,
+            containers,
+            context: definition.get("@context").cloned(),
+            is_protected,
+        };
+        set_term(context, local, term, previous, Some(term_definition))?;
+        defined.insert(term.to_string(), true);
+        Ok(())
+    }
+    /// Expands an IRI while a context is being processed, first defining any terms of that
+    /// context it depends on.
+    fn expand_iri_defining(&self, context: &mut Context, local: &LocalContext, value: &str,
+                           defined: &mut BTreeMap<String, bool>, remote: &mut Vec<String>)
+                           -> Result<Option<String>, PenyuError> {
+        if local.definitions.contains_key(value) && defined.get(value) != Some(&true) {
+            self.define_term(context, local, value, defined, remote)?;
+        }
+        if let Some((prefix, _)) = value.split_once(':') {
+            if local.definitions.contains_key(prefix) && defined.get(prefix) != Some(&true) {
+                self.define_term(context, local, prefix, defined, remote)?;
+            }
+        }
+        Ok(context.expand_iri(value, false, true))
+    }
+    fn expand_document(&self, document: &Value) -> Result<Value, PenyuError> {
+        let expanded = self.expand(&Rc::default(), None, document)?;
+        let expanded =
+            match expanded {
+                Value::Object(mut map) if map.len() == 1 && map.contains_key("@graph") => {
+                    map.remove("@graph").unwrap()
+                }
+                Value::Null => { Value::Array(Vec::new()) }
+                Value::Array(items) => { Value::Array(items) }
+                other => { Value::Array(vec![other]) }
+            };
+        Ok(expanded)
+    }
+    fn expand(&self, context: &Rc<Context>, property: Option<&str>, element: &Value)
+              -> Result<Value, PenyuError> {
+        match element {
+            Value::Null => { Ok(Value::Null) }
+            Value::Array(items) => {
+                let mut expanded_items: Vec<Value> = Vec::new();
+                for item in items {
+                    match self.expand(context, property, item)? {
+                        Value::Null => {}
+                        Value::Array(nested) => { expanded_items.extend(nested); }
+                        expanded => { expanded_items.push(expanded); }
+                    }
+                }
+                Ok(Value::Array(expanded_items))
+            }
+            Value::Object(map) => { self.expand_object(context, property, map) }
+            _ => {
+                match property {
+                    None | Some("@graph") => { Ok(Value::Null) }
+                    Some(property) => {
+                        let context = self.property_scoped(context, context, property)?;
+                        Ok(expand_value(&context, property, element))
+                    }
+                }
+            }
+        }
+    }
+    /// Applies the scoped context that the property has in the defining context, if any, to
+    /// the active context. Scoped contexts of properties may redefine protected terms.
+    fn property_scoped(&self, defining: &Context, active: &Rc<Context>, property: &str)
+                       -> Result<Rc<Context>, PenyuError> {
+        match defining.term(property).and_then(|term| term.context.as_ref()) {
+            Some(local) => { self.process_context(active, local, &mut Vec::new(), true, true) }
+            None => { Ok(Rc::clone(active)) }
+        }
+    }
+    fn expand_object(&self, context: &Rc<Context>, property: Option<&str>,
+                     map: &Map<String, Value>) -> Result<Value, PenyuError> {
+        // A context that does not propagate ends at nested nodes, but not at values.
+        let active =
+            match &context.previous {
+                Some(previous) if !is_value_or_id_only(context, map) => { previous }
+                _ => { context }
+            };
+        let mut context =
+            match property {
+                Some(property) => { self.property_scoped(context, active, property)? }
+                None => { Rc::clone(active) }
+            };
+        if let Some(local) = map.get("@context") {
+            context = self.process_context(&context, local, &mut Vec::new(), false, true)?;
+        }
+        let mut type_scoped: Vec<&str> = Vec::new();
+        for (key, value) in map {
+            if context.expand_iri(key, false, true).as_deref() == Some("@type") {
+                type_scoped.extend(as_array(value).iter().filter_map(Value::as_str));
+            }
+        }
+        type_scoped.sort();
+        let type_scoped_context = Rc::clone(&context);
+        for type_term in type_scoped {
+            let local = type_scoped_context.term(type_term).and_then(|term| term.context.as_ref());
+            if let Some(local) = local {
+                context = self.process_context(&context, local, &mut Vec::new(), false, false)?;
+            }
+        }
+        let mut result: Map<String, Value> = Map::new();
+        self.expand_entries(&context, property, map, &mut result)?;
+        post_process(result, property)
+    }
+    /// Expands the entries of a node into the result, including those nested under `@nest`.
+    fn expand_entries(&self, context: &Rc<Context>, property: Option<&str>,
+                      map: &Map<String, Value>, result: &mut Map<String, Value>)
+                      -> Result<(), PenyuError> {
+        let mut nested: Vec<&Value> = Vec::new();
+        for (key, value) in map {
+            if key == "@context" {
+                continue;
+            }
+            let expanded_property =
+                match context.expand_iri(key, false, true) {
+                    Some(expanded_property) => { expanded_property }
+                    None => { continue; }
+                };
+            if expanded_property == "@nest" && property != Some("@reverse") {
+                nested.extend(as_array(value));
+            } else if is_keyword(&expanded_property) {
+                self.expand_keyword(context, property, &expanded_property, value, result)?;
+            } else if expanded_property.contains(':') {
+                self.expand_property(context, key, &expanded_property, value, result)?;
+            }
+        }
+        for nested in nested {
+            let invalid = || PenyuError::from(format!("Invalid @nest value {}", nested));
+            let nested = nested.as_object().ok_or_else(invalid)?;
+            let has_value =
+                nested.keys().any(|key| {
+                    context.expand_iri(key, false, true).as_deref() == Some("@value")
+                });
+            if has_value {
+                return Err(invalid());
+            }
+            self.expand_entries(context, property, nested, result)?;
+        }
+        Ok(())
+    }
+    fn expand_keyword(&self, context: &Rc<Context>, property: Option<&str>, keyword: &str,
+                      value: &Value, result: &mut Map<String, Value>) -> Result<(), PenyuError> {
+        if property == Some("@reverse") {
+            return Err(PenyuError::from(format!("Keyword {} in a reverse property map", keyword)));
+        }
+        if result.contains_key(keyword) && keyword != "@type" && keyword != "@included" {
+            return Err(PenyuError::from(format!("Colliding keywords {}", keyword)));
+        }
+        let invalid = || PenyuError::from(format!("Invalid {} value {}", keyword, value));
+        let expanded_value =
+            match keyword {
+                "@id" => {
+                    let id = value.as_str().ok_or_else(invalid)?;
+                    Value::String(context.expand_iri(id, true, false).unwrap_or_default())
+                }
+                "@type" => {
+                    let types =
+                        as_array(value).iter().map(|type_iri| {
+                            type_iri.as_str().ok_or_else(invalid)
+                        }).collect::<Result<Vec<&str>, _>>()?;
+                    let types: Vec<Value> =
+                        types.into_iter().filter_map(|type_iri| {
+                            context.expand_iri(type_iri, true, true).map(Value::String)
+                        }).collect();
+                    match value {
+                        Value::Array(_) => { Value::Array(types) }
+                        _ => { types.into_iter().next().unwrap_or(Value::Null) }
+                    }
+                }
+                "@graph" => {
+                    Value::Array(as_array(&self.expand(context, Some("@graph"), value)?).to_vec())
+                }
+                "@value" => { value.clone() }
+                "@language" | "@index" | "@direction" => {
+                    if !value.is_string() {
+                        return Err(invalid());
+                    }
+                    value.clone()
+                }
+                "@list" => {
+                    if matches!(property, None | Some("@graph")) {
+                        return Ok(());
+                    }
+                    Value::Array(as_array(&self.expand(context, property, value)?).to_vec())
+                }
+                "@set" => { self.expand(context, property, value)? }
+                "@reverse" => {
+                    if !value.is_object() {
+                        return Err(invalid());
+                    }
+                    let expanded = self.expand(context, Some("@reverse"), value)?;
+                    if let Value::Object(expanded) = expanded {
+                        for (reverse_property, items) in expanded {
+                            if reverse_property == "@reverse" {
+                                if let Value::Object(double_reverse) = items {
+                                    for (forward_property, items) in double_reverse {
+                                        add_value(result, &forward_property, items);
+                                    }
+                                }
+                            } else {
+                                add_reverse(result, &reverse_property, items)?;
+                            }
+                        }
+                    }
+                    return Ok(());
+                }
+                "@included" => {
+                    let included = self.expand(context, None, value)?;
+                    let mut nodes = as_array(&included).to_vec();
+                    let is_node = |item: &Value| {
+                        item.is_object() && item.get("@value").is_none()
+                            && item.get("@list").is_none()
+                    };
+                    if !nodes.iter().all(is_node) {
+                        return Err(invalid());
+                    }
+                    if let Some(Value::Array(existing)) = result.remove("@included") {
+                        nodes.splice(0..0, existing);
+                    }
+                    Value::Array(nodes)
+                }
+                _ => { return Ok(()); }
+            };
+        if !expanded_value.is_null() || keyword == "@value" {
+            result.insert(keyword.to_string(), expanded_value);
+        }
+        Ok(())
+    }
+    fn expand_property(&self, context: &Rc<Context>, key: &str, expanded_property: &str,
+                       value: &Value, result: &mut Map<String, Value>)
+                       -> Result<(), PenyuError> {
+        let term = context.term(key);
+        let no_containers = BTreeSet::new();
+        let containers = term.map(|term| &term.containers).unwrap_or(&no_containers);
+        let expanded_value =
+            if term.and_then(|term| term.type_mapping.as_deref()) == Some("@json") {
+                let mut json = Map::new();
+                json.insert("@value".to_string(), value.clone());
+                json.insert("@type".to_string(), Value::String("@json".to_string()));
+                Value::Object(json)
+            } else if let (true, Value::Object(languages)) =
+                (containers.contains("@language"), value) {
+                let mut items: Vec<Value> = Vec::new();
+                for (language, strings) in languages {
+                    for string in as_array(strings) {
+                        if string.is_null() {
+                            continue;
+                        }
+                        if !string.is_string() {
+                            return Err(PenyuError::from(format!(
+                                "Invalid language map value {}", string
+                            )));
+                        }
+                        let mut item = Map::new();
+                        item.insert("@value".to_string(), string.clone());
+                        let is_none =
+                            context.expand_iri(language, false, true).as_deref() == Some("@none");
+                        if !is_none {
+                            item.insert("@language".to_string(), Value::String(language.clone()));
+                        }
+                        items.push(Value::Object(item));
+                    }
+                }
+                Value::Array(items)
+            } else if let (Some(container), Value::Object(map)) =
+                (["@index", "@id", "@type"].into_iter().find(|c| containers.contains(*c)), value) {
+                let mut items: Vec<Value> = Vec::new();
+                for (index, values) in map {
+                    let expanded = self.expand(context, Some(key), values)?;
+                    for mut item in as_array(&expanded).to_vec() {
+                        if index != "@none" {
+                            index_item(context, container, index, &mut item);
+                        }
+                        items.push(item);
+                    }
+                }
+                Value::Array(items)
+            } else {
+                self.expand(context, Some(key), value)?
+            };
+        if expanded_value.is_null() {
+            return Ok(());
+        }
+        let expanded_value =
+            if containers.contains("@list") && !is_list_object(&expanded_value) {
+                let mut list = Map::new();
+                list.insert("@list".to_string(), Value::Array(as_array(&expanded_value).to_vec()));
+                Value::Object(list)
+            } else if containers.contains("@graph") && !containers.contains("@id")
+                && !containers.contains("@index") {
+                Value::Array(as_array(&expanded_value).iter().map(|item| {
+                    let mut graph = Map::new();
+                    graph.insert("@graph".to_string(), Value::Array(as_array(item).to_vec()));
+                    Value::Object(graph)
+                }).collect())
+            } else {
+                expanded_value
+            };
+        if term.is_some_and(|term| term.is_reverse) {
+            add_reverse(result, expanded_property, expanded_value)
+        } else {
+            add_value(result, expanded_property, expanded_value);
+            Ok(())
+        }
+    }
+}
+
+/// Sets the term, unless it would change a protected term outside a property-scoped context.
+fn set_term(context: &mut Context, local: &LocalContext, term: &str, previous: Option<Term>,
+            definition: Option<Term>) -> Result<(), PenyuError> {
+    if let Some(previous) = previous.filter(|previous| previous.is_protected) {
+        if !local.override_protected {
+            let is_same =
+                definition.is_some_and(|definition| {
+                    Term { is_protected: true, ..definition } == previous
+                });
+            if !is_same {
+                return Err(PenyuError::from(format!("Redefinition of protected term {}", term)));
+            }
+            context.terms.insert(term.to_string(), Some(previous));
+            return Ok(());
+        }
+    }
+    context.terms.insert(term.to_string(), definition);
+    Ok(())
+}
+
+/// Whether the map is a value object or only has an `@id`, which keep the active context
+/// even where it does not propagate.
+fn is_value_or_id_only(context: &Context, map: &Map<String, Value>) -> bool {
+    let keywords: Vec<Option<String>> =
+        map.keys().map(|key| context.expand_iri(key, false, true)).collect();
+    keywords.iter().any(|keyword| keyword.as_deref() == Some("@value"))
+        || (keywords.len() == 1 && keywords[0].as_deref() == Some("@id"))
+}
+
+fn expand_value(context: &Context, property: &str, value: &Value) -> Value {
+    let term = context.term(property);
+    let mut result = Map::new();
+    match (term.and_then(|term| term.type_mapping.as_deref()), value) {
+        (Some("@id"), Value::String(id)) => {
+            let id = context.expand_iri(id, true, false).unwrap_or_default();
+            result.insert("@id".to_string(), Value::String(id));
+        }
+        (Some("@vocab"), Value::String(id)) => {
+            let id = context.expand_iri(id, true, true).unwrap_or_default();
+            result.insert("@id".to_string(), Value::String(id));
+        }
+        (Some(type_mapping), _) if !matches!(type_mapping, "@id" | "@vocab" | "@none") => {
+            result.insert("@value".to_string(), value.clone());
+            result.insert("@type".to_string(), Value::String(type_mapping.to_string()));
+        }
+        _ => {
+            result.insert("@value".to_string(), value.clone());
+            if value.is_string() {
+                let language =
+                    match term.and_then(|term| term.language.as_ref()) {
+                        Some(language) => { language.as_ref() }
+                        None => { context.language.as_ref() }
+                    };
+                if let Some(language) = language {
+                    result.insert("@language".to_string(), Value::String(language.clone()));
+                }
+            }
+        }
+    }
+    Value::Object(result)
+}
+
+fn index_item(context: &Context, container: &str, index: &str, item: &mut Value) {
+    if let Value::Object(item) = item {
+        match container {
+            "@index" => {
+                item.entry("@index").or_insert_with(|| Value::String(index.to_string()));
+            }
+            "@id" => {
+                let id = context.expand_iri(index, true, false).unwrap_or_default();
+                item.entry("@id").or_insert(Value::String(id));
+            }
+            _ => {
+                let type_iri = context.expand_iri(index, true, true).unwrap_or_default();
+                let mut types = vec![Value::String(type_iri)];
+                if let Some(existing) = item.remove("@type") {
+                    types.extend(as_array(&existing).iter().cloned());
+                }
+                item.insert("@type".to_string(), Value::Array(types));
+            }
+        }
+    }
+}
+
+fn post_process(mut result: Map<String, Value>, property: Option<&str>)
+                -> Result<Value, PenyuError> {
+    if let Some(value) = result.get("@value") {
+        if result.keys().any(|key| {
+            !matches!(key.as_str(), "@value" | "@language" | "@type" | "@index" | "@direction")
+        }) {
+            return Err(PenyuError::from("Invalid value object"));
+        }
+        // A JSON literal may have any value, while other values must be scalars.
+        let is_json = result.get("@type").and_then(Value::as_str) == Some("@json");
+        if value.is_null() && !is_json {
+            return Ok(Value::Null);
+        }
+        if (value.is_object() || value.is_array()) && !is_json {
+            return Err(PenyuError::from(format!("Invalid @value value {}", value)));
+        }
+        if result.contains_key("@language") && !value.is_string() {
+            return Err(PenyuError::from(format!("Invalid language-tagged value {}", value)));
+        }
+        if result.get("@type").is_some_and(|type_iri| !type_iri.is_string()) {
+            return Err(PenyuError::from("Invalid typed value"));
+        }
+    } else if let Some(types) = result.get_mut("@type") {
+        if !types.is_array() {
+            *types = Value::Array(vec![types.take()]);
+        }
+    } else if result.contains_key("@set") || result.contains_key("@list") {
+        if result.keys().any(|key| !matches!(key.as_str(), "@set" | "@list" | "@index")) {
+            return Err(PenyuError::from("Invalid set or list object"));
+        }
+        if let Some(set) = result.remove("@set") {
+            return Ok(set);
+        }
+    }
+    if result.len() == 1 && result.contains_key("@language") {
+        return Ok(Value::Null);
+    }
+    if matches!(property, None | Some("@graph"))
+        && (result.is_empty() || result.contains_key("@value") || result.contains_key("@list")
+        || (result.len() == 1 && result.contains_key("@id"))) {
+        return Ok(Value::Null);
+    }
+    Ok(Value::Object(result))
+}
+
+fn add_value(result: &mut Map<String, Value>, key: &str, value: Value) {
+    let values = result.entry(key).or_insert_with(|| Value::Array(Vec::new()));
+    if let Value::Array(values) = values {
+        match value {
+            Value::Array(items) => { values.extend(items); }
+            value => { values.push(value); }
+        }
+    }
+}
+
+fn add_reverse(result: &mut Map<String, Value>, key: &str, value: Value)
+               -> Result<(), PenyuError> {
+    for item in as_array(&value) {
+        if item.get("@value").is_some() || item.get("@list").is_some() {
+            return Err(PenyuError::from(format!("Invalid reverse property value {}", item)));
+        }
+    }
+    let reverse = result.entry("@reverse").or_insert_with(|| Value::Object(Map::new()));
+    if let Value::Object(reverse) = reverse {
+        add_value(reverse, key, value);
+    }
+    Ok(())
+}
+
+fn as_array(value: &Value) -> &[Value] {
+    match value {
+        Value::Array(items) => { items }
+        value => { std::slice::from_ref(value) }
+    }
+}
+
+fn is_list_object(value: &Value) -> bool {
+    value.get("@list").is_some()
+}
+
+fn is_keyword(value: &str) -> bool {
+    matches!(value, "@base" | "@container" | "@context" | "@direction" | "@graph" | "@id"
+        | "@import" | "@included" | "@index" | "@json" | "@language" | "@list" | "@nest"
+        | "@none" | "@prefix" | "@propagate" | "@protected" | "@reverse" | "@set" | "@type"
+        | "@value" | "@version" | "@vocab")
+}
+
+fn looks_like_keyword(value: &str) -> bool {
+    value.strip_prefix('@').is_some_and(|rest| {
+        !rest.is_empty() && rest.chars().all(|c| c.is_ascii_alphabetic())
+    })
+}
+
+/// Turns expanded node objects into quads.
+struct Builder {
+    dataset: MemoryDataset,
+    blank_nodes: BlankNodes,
+}
+
+impl Builder {
+    fn entity(&mut self, id: &str) -> Option<Entity> {
+        match id.strip_prefix("_:") {
+            Some(label) => { Some(Entity::from(self.blank_nodes.labeled(label.to_string()))) }
+            None if syntax::is_absolute_iri(id) => { Some(Entity::from(Iri::from(id.to_string()))) }
+            None => { None }
+        }
+    }
+    fn add_node(&mut self, node: &Map<String, Value>, graph_name: &Option<Entity>)
+                -> Result<Option<Entity>, PenyuError> {
+        let subject =
+            match node.get("@id").and_then(Value::as_str) {
+                Some(id) => {
+                    match self.entity(id) {
+                        Some(subject) => { subject }
+                        None => { return Ok(None); }
+                    }
+                }
+                None => { Entity::from(self.blank_nodes.fresh()) }
+            };
+        for (key, value) in node {
+            match key.as_str() {
+                "@type" => {
+                    for type_iri in as_array(value).iter().filter_map(Value::as_str) {
+                        if let Some(object) = self.entity(type_iri) {
+                            self.dataset.add(&subject, rdf::TYPE, object, graph_name.clone());
+                        }
+                    }
+                }
+                "@graph" => {
+                    let graph_name = Some(subject.clone());
+                    for item in as_array(value) {
+                        if let Value::Object(item) = item {
+                            if !item.contains_key("@value") && !item.contains_key("@list") {
+                                self.add_node(item, &graph_name)?;
+                            }
+                        }
+                    }
+                }
+                "@included" => {
+                    for item in as_array(value) {
+                        if let Value::Object(item) = item {
+                            self.add_node(item, graph_name)?;
+                        }
+                    }
+                }
+                "@reverse" => {
+                    for (property, items) in value.as_object().into_iter().flatten() {
+                        if !syntax::is_absolute_iri(property) {
+                            continue;
+                        }
+                        let predicate = Iri::from(property.clone());
+                        for item in as_array(items) {
+                            if let Value::Object(item) = item {
+                                if let Some(reverse_subject) = self.add_node(item, graph_name)? {
+                                    self.dataset.add(reverse_subject, &predicate, &subject,
+                                                     graph_name.clone());
+                                }
+                            }
+                        }
+                    }
+                }
+                property if property.starts_with('@') || !syntax::is_absolute_iri(property) => {}
+                property => {
+                    let predicate = Iri::from(property.to_string());
+                    for item in as_array(value) {
+                        if let Some(object) = self.object(item, graph_name)? {
+                            self.dataset.add(&subject, &predicate, object, graph_name.clone());
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Some(subject))
+    }
+    fn object(&mut self, item: &Value, graph_name: &Option<Entity>)
+              -> Result<Option<Node>, PenyuError> {
+        match item {
+            Value::Object(map) => {
+                if map.contains_key("@value") {
+                    literal(map)
+                } else if let Some(list) = map.get("@list") {
+                    self.list(as_array(list), graph_name).map(Some)
+                } else {
+                    Ok(self.add_node(map, graph_name)?.map(Node::from))
+                }
+            }
+            _ => { Ok(None) }
+        }
+    }
+    fn list(&mut self, items: &[Value], graph_name: &Option<Entity>)
+            -> Result<Node, PenyuError> {
+        let mut objects: Vec<Node> = Vec::new();
+        for item in items {
+            if let Some(object) = self.object(item, graph_name)? {
+                objects.push(object);
+            }
+        }
+        let mut rest = Node::from(rdf::NIL);
+        for object in objects.into_iter().rev() {
+            let cell = self.blank_nodes.fresh();
+            self.dataset.add(&cell, rdf::FIRST, object, graph_name.clone());
+            self.dataset.add(&cell, rdf::REST, rest, graph_name.clone());
+            rest = Node::from(cell);
+        }
+        Ok(rest)
+    }
+}
+
+fn literal(map: &Map<String, Value>) -> Result<Option<Node>, PenyuError> {
+    let value = &map["@value"];
+    let datatype = map.get("@type").and_then(Value::as_str);
+    if datatype == Some("@json") {
+        let json = serde_json::to_string(value)?;
+        return Ok(Some(Node::from(Literal::new(json, LiteralTag::Type(rdf::JSON.clone())))));
+    }
+    if datatype.is_some_and(|datatype| !syntax::is_absolute_iri(datatype)) {
+        return Ok(None);
+    }
+    let typed = |lexical: String, default: &Iri| {
+        let datatype = datatype.map(|datatype| Iri::from(datatype.to_string()));
+        Literal::new(lexical, LiteralTag::Type(datatype.unwrap_or_else(|| default.clone())))
+    };
+    let literal =
+        match value {
+            Value::String(string) => {
+                match map.get("@language").and_then(Value::as_str) {
+                    Some(language) => {
+                        Literal::new(string.clone(), LiteralTag::lang(language)?)
+                    }
+                    None => { typed(string.clone(), xsd::STRING) }
+                }
+            }
+            Value::Bool(boolean) => { typed(boolean.to_string(), xsd::BOOLEAN) }
+            Value::Number(number) => {
+                let is_double = datatype == Some(xsd::DOUBLE.to_string().as_str());
+                match (number.as_i64(), number.as_u64(), number.as_f64()) {
+                    (Some(integer), _, _) if !is_double => {
+                        typed(integer.to_string(), xsd::INTEGER)
+                    }
+                    (_, Some(integer), _) if !is_double => {
+                        typed(integer.to_string(), xsd::INTEGER)
+                    }
+                    (_, _, Some(x)) if !is_double && x.fract() == 0.0 && x.abs() < 1e21 => {
+                        typed(format!("{:.0}", x), xsd::INTEGER)
+                    }
+                    (_, _, Some(x)) => {
+                        typed(crate::model::value::Value::Double(x).to_string(), xsd::DOUBLE)
+                    }
+                    _ => { return Err(PenyuError::from(format!("Invalid number {}", number))); }
+                }
+            }
+            _ => { return Err(PenyuError::from(format!("Invalid value {}", value))); }
+        };
+    Ok(Some(Node::from(literal)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::PenyuError;
+    use crate::model::dataset::Dataset;
+    use crate::model::graph::{Graph, MemoryGraph};
+    use crate::model::iri::Iri;
+    use serde_json::{json, Value};
+    use std::cell::Cell;
+    use std::collections::BTreeMap;
+
+    fn n_triples(graph: &MemoryGraph) -> Vec<String> {
+        let mut bytes: Vec<u8> = Vec::new();
+        crate::write::ntriples::write(&mut bytes, graph).unwrap();
+        let mut lines: Vec<String> =
+            String::from_utf8(bytes).unwrap().lines().map(str::to_string).collect();
+        lines.sort();
+        lines
+    }
+
+    #[test]
+    fn expands_and_converts() {
+        let document = json!({
+            "@context": [
+                "http://example.org/context.jsonld",
+                {
+                    "@base": "http://example.org/data/",
+                    "label": { "@id": "rdfs:label", "@container": "@language" },
+                    "partOf": { "@id": "ex:partOf", "@type": "@id" },
+                    "hasPart": { "@reverse": "ex:partOf" },
+                    "steps": { "@id": "ex:steps", "@container": "@list" },
+                    "weight": { "@id": "ex:weight", "@type": "xsd:decimal" }
+                }
+            ],
+            "@id": "liver",
+            "@type": "Organ",
+            "label": { "en": "liver", "de": "Leber" },
+            "partOf": "body",
+            "hasPart": { "@id": "lobe" },
+            "steps": [ "a", 2, 1.5, true ],
+            "weight": "1.5",
+            "ex:note": { "@value": "n", "@language": "fr" },
+            "ignored": "x"
+        });
+        let mut loader: BTreeMap<String, Value> = BTreeMap::new();
+        loader.insert("http://example.org/context.jsonld".to_string(), json!({
+            "@context": {
+                "ex": "http://example.org/ns#",
+                "rdfs": "http://www.w3.org/2000/01/rdf-schema#",
+                "xsd": "http://www.w3.org/2001/XMLSchema#",
+                "Organ": "ex:Organ"
+            }
+        }));
+        let bytes = serde_json::to_vec(&document).unwrap();
+        let graph = super::read_with_loader(&mut bytes.as_slice(), &loader).unwrap();
+        let lines = n_triples(&graph);
+        let liver = "<http://example.org/data/liver>";
+        let expected = [
+            format!("<http://example.org/data/lobe> <http://example.org/ns#partOf> {} .", liver),
+            format!("{} <http://example.org/ns#note> \"n\"@fr .", liver),
+            format!("{} <http://example.org/ns#partOf> <http://example.org/data/body> .", liver),
+            format!("{} <http://example.org/ns#weight> \
+                     \"1.5\"^^<http://www.w3.org/2001/XMLSchema#decimal> .", liver),
+            format!("{} <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> \
+                     <http://example.org/ns#Organ> .", liver),
+            format!("{} <http://www.w3.org/2000/01/rdf-schema#label> \"Leber\"@de .", liver),
+            format!("{} <http://www.w3.org/2000/01/rdf-schema#label> \"liver\"@en .", liver),
+        ];
+        for line in &expected {
+            assert!(lines.contains(line), "{}\n{:#?}", line, lines);
+        }
+        let list_items: Vec<&String> =
+            lines.iter().filter(|line| line.contains("#first>")).collect();
+        assert_eq!(list_items.len(), 4);
+        assert!(lines.iter().any(|line| {
+            line.ends_with("\"1.5E0\"^^<http://www.w3.org/2001/XMLSchema#double> .")
+        }));
+        assert!(lines.iter().any(|line| {
+            line.ends_with("\"2\"^^<http://www.w3.org/2001/XMLSchema#integer> .")
+        }));
+        assert_eq!(lines.len(), expected.len() + 9);
+        assert!(graph.prefixes().contains_key("ex"));
+        assert!(!graph.prefixes().contains_key("Organ"));
+        assert!(super::read(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn reads_named_graphs() {
+        let document = r#"{
+            "@context": { "@vocab": "http://example.org/" },
+            "@graph": [
+                { "@id": "http://example.org/a", "p": { "@id": "http://example.org/b" } },
+                { "@id": "http://example.org/g", "@graph": { "@id": "_:x", "p": 1 } }
+            ]
+        }"#;
+        let dataset =
+            super::read_dataset(&mut document.as_bytes(), &super::NoDocumentLoader).unwrap();
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(dataset.graph_names().count(), 1);
+        assert!(super::read(&mut "{ \"@context\": 3 }".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn loads_each_remote_context_once() {
+        struct CountingLoader {
+            n_loads: Cell<usize>,
+        }
+        impl super::DocumentLoader for CountingLoader {
+            fn load_document(&self, _iri: &Iri) -> Result<Value, PenyuError> {
+                self.n_loads.set(self.n_loads.get() + 1);
+                Ok(json!({ "@context": { "@vocab": "http://example.org/" } }))
+            }
+        }
+        let node = |id: &str| {
+            json!({ "@context": "http://example.org/context.jsonld", "@id": id, "p": 1 })
+        };
+        let document = json!([ node("http://example.org/a"), node("http://example.org/b") ]);
+        let loader = CountingLoader { n_loads: Cell::new(0) };
+        let bytes = serde_json::to_vec(&document).unwrap();
+        let graph = super::read_with_loader(&mut bytes.as_slice(), &loader).unwrap();
+        assert_eq!(graph.len(), 2);
+        assert_eq!(loader.n_loads.get(), 1);
+    }
+
+    #[test]
+    fn reads_inline_json_literals() {
+        let document = json!({
+            "@id": "http://example.org/a",
+            "http://example.org/data": { "@value": { "b": [ 1, null ], "a": 1 }, "@type": "@json" },
+            "http://example.org/none": { "@type": "@json", "@value": null }
+        });
+        let bytes = serde_json::to_vec(&document).unwrap();
+        let lines = n_triples(&super::read(&mut bytes.as_slice()).unwrap());
+        let expected = [
+            "<http://example.org/a> <http://example.org/data> \"{\\\"a\\\":1,\\\"b\\\":[1,null]}\"\
+             ^^<http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON> .",
+            "<http://example.org/a> <http://example.org/none> \
+             \"null\"^^<http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON> .",
+        ];
+        assert_eq!(lines, expected);
+        let not_json = json!({
+            "@id": "http://example.org/a",
+            "http://example.org/data": { "@value": { "a": 1 } }
+        });
+        let bytes = serde_json::to_vec(&not_json).unwrap();
+        assert!(super::read(&mut bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn nests_includes_and_protects() {
+        let document = json!({
+            "@context": {
+                "@vocab": "http://example.org/",
+                "@protected": true,
+                "labels": "@nest",
+                "name": "http://example.org/name",
+                "knows": {
+                    "@id": "http://example.org/knows",
+                    "@context": { "name": "http://example.org/nickname" }
+                },
+                "Person": {
+                    "@id": "http://example.org/Person",
+                    "@context": { "@protected": false, "age": "http://example.org/years" }
+                }
+            },
+            "@id": "http://example.org/a",
+            "@type": "Person",
+            "labels": { "name": "A", "labels": { "age": 3 } },
+            "knows": { "@id": "http://example.org/b", "name": "B", "age": 4 },
+            "@included": [ { "@id": "http://example.org/c", "name": "C" } ]
+        });
+        let bytes = serde_json::to_vec(&document).unwrap();
+        let lines = n_triples(&super::read(&mut bytes.as_slice()).unwrap());
+        let expected = [
+            "<http://example.org/a> <http://example.org/knows> <http://example.org/b> .",
+            "<http://example.org/a> <http://example.org/name> \"A\" .",
+            "<http://example.org/a> <http://example.org/years> \
+             \"3\"^^<http://www.w3.org/2001/XMLSchema#integer> .",
+            "<http://example.org/a> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> \
+             <http://example.org/Person> .",
+            "<http://example.org/b> <http://example.org/age> \
+             \"4\"^^<http://www.w3.org/2001/XMLSchema#integer> .",
+            "<http://example.org/b> <http://example.org/nickname> \"B\" .",
+            "<http://example.org/c> <http://example.org/name> \"C\" .",
+        ];
+        assert_eq!(lines, expected);
+        let redefined = json!({
+            "@context": [
+                { "@protected": true, "name": "http://example.org/name" },
+                { "name": "http://example.org/other" }
+            ],
+            "name": "A"
+        });
+        let bytes = serde_json::to_vec(&redefined).unwrap();
+        assert!(super::read(&mut bytes.as_slice()).is_err());
+        let nullified = json!({
+            "@context": [ { "@protected": true, "name": "http://example.org/name" }, null ],
+            "name": "A"
+        });
+        let bytes = serde_json::to_vec(&nullified).unwrap();
+        assert!(super::read(&mut bytes.as_slice()).is_err());
+        let invalid_nest = json!({ "@nest": "x", "http://example.org/name": "A" });
+        let bytes = serde_json::to_vec(&invalid_nest).unwrap();
+        assert!(super::read(&mut bytes.as_slice()).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use crate::error::PenyuError;
+use crate::model::dataset::MemoryDataset;
+use crate::model::quad::Quad;
+use crate::read::ntriples::LineParser;
+use std::io::{BufRead, BufReader, Read};
+
+pub fn read<R: Read>(read: &mut R) -> Result<MemoryDataset, PenyuError> {
+    let mut dataset = MemoryDataset::new();
+    for quad in quads(BufReader::new(read)) {
+        dataset.add_quad(quad?);
+    }
+    Ok(dataset)
+}
+
+pub fn quads<R: BufRead>(read: R) -> QuadReader<R> {
+    QuadReader { read, line_number: 0, line: String::new() }
+}
+
+pub struct QuadReader<R: BufRead> {
+    read: R,
+    line_number: usize,
+    line: String,
+}
+
+impl<R: BufRead> Iterator for QuadReader<R> {
+    type Item = Result<Quad, PenyuError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            match self.read.read_line(&mut self.line) {
+                Ok(0) => { return None; }
+                Ok(_) => {
+                    self.line_number += 1;
+                    match parse_line(&self.line, self.line_number) {
+                        Ok(Some(quad)) => { return Some(Ok(quad)); }
+                        Ok(None) => {}
+                        Err(error) => { return Some(Err(error)); }
+                    }
+                }
+                Err(error) => { return Some(Err(PenyuError::from(error))); }
+            }
+        }
+    }
+}
+
+fn parse_line(line: &str, line_number: usize) -> Result<Option<Quad>, PenyuError> {
+    let mut parser = LineParser::new(line, line_number);
+    if parser.is_at_end() {
+        return Ok(None);
+    }
+    let triple = parser.parse_triple()?;
+    let graph_name = parser.parse_graph_name()?;
+    parser.parse_end()?;
+    Ok(Some(Quad::from_triple(triple, graph_name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::dataset::Dataset;
+
+    #[test]
+    fn round_trips() {
+        let nquads =
+            "<http://example.org/a> <http://example.org/p> \"x\"@en .\n\
+             # comment\n\
+             <http://example.org/a> <http://example.org/p> _:b1 <http://example.org/g> .\n\
+             _:b1 <http://example.org/p> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> _:g .\n";
+        let dataset = super::read(&mut nquads.as_bytes()).unwrap();
+        assert_eq!(dataset.len(), 3);
+        assert_eq!(dataset.graph_names().count(), 2);
+        let mut bytes: Vec<u8> = Vec::new();
+        crate::write::nquads::write(&mut bytes, &dataset).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(),
+                   "<http://example.org/a> <http://example.org/p> \"x\"@en .\n\
+                    <http://example.org/a> <http://example.org/p> _:b1 <http://example.org/g> .\n\
+                    _:b1 <http://example.org/p> \
+                    \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> _:g .\n");
+        let error = super::read(&mut "<http://example.org/a> <http://example.org/p> \"x\" \"g\" .\n"
+            .as_bytes()).err().unwrap();
+        assert_eq!(error.to_string(), "Expected '.', but found '\"' at line 1, column 51");
+    }
+}
@@ -0,0 +1,347 @@
+use crate::error::PenyuError;
+use crate::model::graph::MemoryGraph;
+use crate::model::iri::Iri;
+use crate::model::literal::{Literal, LiteralTag};
+use crate::model::node::{BlankNode, Entity, Node};
+use crate::model::triple::Triple;
+use crate::syntax;
+use std::io::{BufRead, BufReader, Read};
+
+pub fn read<R: Read>(read: &mut R) -> Result<MemoryGraph, PenyuError> {
+    let mut graph = MemoryGraph::new();
+    for triple in triples(BufReader::new(read)) {
+        graph.add_triple(triple?);
+    }
+    Ok(graph)
+}
+
+pub fn triples<R: BufRead>(read: R) -> TripleReader<R> {
+    TripleReader { read, line_number: 0, line: String::new() }
+}
+
+pub struct TripleReader<R: BufRead> {
+    read: R,
+    line_number: usize,
+    line: String,
+}
+
+impl<R: BufRead> Iterator for TripleReader<R> {
+    type Item = Result<Triple, PenyuError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            match self.read.read_line(&mut self.line) {
+                Ok(0) => { return None; }
+                Ok(_) => {
+                    self.line_number += 1;
+                    match LineParser::new(&self.line, self.line_number).parse_line() {
+                        Ok(Some(triple)) => { return Some(Ok(triple)); }
+                        Ok(None) => {}
+                        Err(error) => { return Some(Err(error)); }
+                    }
+                }
+                Err(error) => { return Some(Err(PenyuError::from(error))); }
+            }
+        }
+    }
+}
+
+pub(crate) struct LineParser<'a> {
+    line: &'a str,
+    pos: usize,
+    line_number: usize,
+}
+
+impl<'a> LineParser<'a> {
+    pub(crate) fn new(line: &'a str, line_number: usize) -> LineParser<'a> {
+        LineParser { line, pos: 0, line_number }
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, PenyuError> {
+        let column = self.line[..self.pos].chars().count() + 1;
+        Err(PenyuError::from(
+            format!("{} at line {}, column {}", message, self.line_number, column)
+        ))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.line[self.pos..].chars().next()
+    }
+    fn next(&mut self) -> Result<char, PenyuError> {
+        match self.peek() {
+            Some(c) => {
+                self.pos += c.len_utf8();
+                Ok(c)
+            }
+            None => { self.error("Unexpected end of line".to_string()) }
+        }
+    }
+    fn expect(&mut self, expected: char) -> Result<(), PenyuError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += c.len_utf8();
+                Ok(())
+            }
+            Some(c) => { self.error(format!("Expected '{}', but found '{}'", expected, c)) }
+            None => { self.error(format!("Expected '{}', but found end of line", expected)) }
+        }
+    }
+    fn skip_ws(&mut self) {
+        while let Some(' ' | '\t') = self.peek() {
+            self.pos += 1;
+        }
+    }
+    pub(crate) fn is_at_end(&mut self) -> bool {
+        self.skip_ws();
+        matches!(self.peek(), None | Some('#' | '\n' | '\r'))
+    }
+
+    fn parse_line(&mut self) -> Result<Option<Triple>, PenyuError> {
+        if self.is_at_end() {
+            return Ok(None);
+        }
+        let triple = self.parse_triple()?;
+        self.parse_end()?;
+        Ok(Some(triple))
+    }
+
+    pub(crate) fn parse_triple(&mut self) -> Result<Triple, PenyuError> {
+        self.skip_ws();
+        let subject = self.parse_entity()?;
+        self.skip_ws();
+        let predicate = self.parse_iri()?;
+        self.skip_ws();
+        let object = self.parse_node()?;
+        Ok(Triple::new(subject, predicate, object))
+    }
+
+    /// The graph name of a quad, if there is one before the final '.'.
+    pub(crate) fn parse_graph_name(&mut self) -> Result<Option<Entity>, PenyuError> {
+        self.skip_ws();
+        match self.peek() {
+            Some('<' | '_') => { Ok(Some(self.parse_entity()?)) }
+            _ => { Ok(None) }
+        }
+    }
+
+    pub(crate) fn parse_end(&mut self) -> Result<(), PenyuError> {
+        self.skip_ws();
+        self.expect('.')?;
+        if self.is_at_end() {
+            Ok(())
+        } else {
+            self.error("Unexpected content after '.'".to_string())
+        }
+    }
+
+    pub(crate) fn parse_entity(&mut self) -> Result<Entity, PenyuError> {
+        match self.peek() {
+            Some('<') => { Ok(Entity::from(self.parse_iri()?)) }
+            Some('_') => { Ok(Entity::from(self.parse_blank_node()?)) }
+            _ => { self.error("Expected IRI or blank node".to_string()) }
+        }
+    }
+
+    fn parse_node(&mut self) -> Result<Node, PenyuError> {
+        match self.peek() {
+            Some('"') => { Ok(Node::from(self.parse_literal()?)) }
+            _ => { Ok(Node::from(self.parse_entity()?)) }
+        }
+    }
+
+    fn parse_iri(&mut self) -> Result<Iri, PenyuError> {
+        self.expect('<')?;
+        let mut iri = String::new();
+        loop {
+            match self.next()? {
+                '>' => {
+                    if !syntax::is_absolute_iri(&iri) {
+                        return self.error(format!("Relative IRI <{}>", iri));
+                    }
+                    return Ok(Iri::from(iri));
+                }
+                '\\' => {
+                    let c =
+                        match self.next()? {
+                            'u' => { self.parse_hex_char(4)? }
+                            'U' => { self.parse_hex_char(8)? }
+                            c => {
+                                return self.error(format!("Invalid escape '\\{}' in IRI", c));
+                            }
+                        };
+                    if !syntax::is_valid_iri_char(c) {
+                        return self.error(format!("Invalid escaped character {:?} in IRI", c));
+                    }
+                    iri.push(c);
+                }
+                c if !syntax::is_valid_iri_char(c) => {
+                    return self.error(format!("Invalid character {:?} in IRI", c));
+                }
+                c => { iri.push(c); }
+            }
+        }
+    }
+
+    fn parse_blank_node(&mut self) -> Result<BlankNode, PenyuError> {
+        self.expect('_')?;
+        self.expect(':')?;
+        let start = self.pos;
+        match self.peek() {
+            Some(c) if syntax::is_pn_chars_u(c) || c.is_ascii_digit() => {
+                self.pos += c.len_utf8();
+            }
+            _ => { return self.error("Invalid blank node label".to_string()); }
+        }
+        while let Some(c) = self.peek() {
+            let continues =
+                syntax::is_pn_chars(c) || (c == '.' && self.line[self.pos..].chars()
+                    .find(|c| *c != '.').is_some_and(syntax::is_pn_chars));
+            if !continues {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+        Ok(BlankNode::from(&self.line[start..self.pos]))
+    }
+
+    fn parse_literal(&mut self) -> Result<Literal, PenyuError> {
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            if matches!(self.peek(), Some('\n' | '\r')) {
+                return self.error("Line break in string".to_string());
+            }
+            match self.next()? {
+                '"' => { break; }
+                '\\' => {
+                    let c = match self.next()? {
+                        't' => { '\t' }
+                        'b' => { '\x08' }
+                        'n' => { '\n' }
+                        'r' => { '\r' }
+                        'f' => { '\x0C' }
+                        '"' => { '"' }
+                        '\'' => { '\'' }
+                        '\\' => { '\\' }
+                        'u' => { self.parse_hex_char(4)? }
+                        'U' => { self.parse_hex_char(8)? }
+                        c => { return self.error(format!("Invalid escape '\\{}'", c)); }
+                    };
+                    string.push(c);
+                }
+                c => { string.push(c); }
+            }
+        }
+        match self.peek() {
+            Some('@') => {
+                self.pos += 1;
+                let start = self.pos;
+                while let Some(c) = self.peek() {
+                    if c.is_ascii_alphanumeric() || c == '-' {
+                        self.pos += 1;
+                    } else {
+                        break;
+                    }
+                }
+                let lang_tag = &self.line[start..self.pos];
+                if lang_tag.is_empty() {
+                    return self.error("Empty language tag".to_string());
+                }
+                match LiteralTag::lang(lang_tag) {
+                    Ok(tag) => { Ok(Literal::new(string, tag)) }
+                    Err(error) => { self.error(error.to_string()) }
+                }
+            }
+            Some('^') => {
+                self.pos += 1;
+                self.expect('^')?;
+                let datatype = self.parse_iri()?;
+                Ok(Literal::new(string, LiteralTag::Type(datatype)))
+            }
+            _ => { Ok(Literal::from(string)) }
+        }
+    }
+
+    fn parse_hex_char(&mut self, len: usize) -> Result<char, PenyuError> {
+        let mut code: u32 = 0;
+        for _ in 0..len {
+            let c = self.next()?;
+            match c.to_digit(16) {
+                Some(digit) => { code = code * 16 + digit; }
+                None => { return self.error(format!("Invalid hex digit '{}'", c)); }
+            }
+        }
+        match char::from_u32(code) {
+            Some(c) => { Ok(c) }
+            None => { self.error(format!("Invalid code point {:X}", code)) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::graph::{Graph, MemoryGraph};
+    use crate::model::literal::{Literal, LiteralTag};
+    use crate::model::node::Node;
+    use crate::model::triple::Triple;
+
+    #[test]
+    fn round_trips() {
+        let ntriples =
+            "<http://example.org/a> <http://example.org/p> \"x\\ty \\\"z\\\"\"@en-US .\n\
+             <http://example.org/a> <http://example.org/p> \
+             \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n\
+             <http://example.org/a> <http://example.org/q> _:b.1 .\n\
+             _:b.1 <http://example.org/p> <http://example.org/\\u00E9> .\n";
+        let graph = super::read(&mut ntriples.as_bytes()).unwrap();
+        assert_eq!(graph.len(), 4);
+        let mut bytes: Vec<u8> = Vec::new();
+        crate::write::ntriples::write(&mut bytes, &graph).unwrap();
+        let graph2: MemoryGraph = super::read(&mut bytes.as_slice()).unwrap();
+        let triples: Vec<Triple> = graph.triples().collect();
+        assert!(triples == graph2.triples().collect::<Vec<Triple>>());
+    }
+
+    #[test]
+    fn streams_and_reports_line() {
+        let ntriples =
+            "# comment\n\
+             <http://example.org/a> <http://example.org/p> <http://example.org/b> .\n\
+             \n\
+             <http://example.org/a> <http://example.org/p> \"unterminated .\n";
+        let mut triples = super::triples(ntriples.as_bytes());
+        assert!(triples.next().unwrap().is_ok());
+        let error = triples.next().unwrap().err().unwrap();
+        assert_eq!(error.to_string(), "Line break in string at line 4, column 62");
+        assert!(triples.next().is_none());
+    }
+
+    #[test]
+    fn validates_lang_tags() {
+        let ntriples = "<http://example.org/a> <http://example.org/p> \"x\"@EN-us .\n\
+                        <http://example.org/a> <http://example.org/p> \"y\"@123 .\n";
+        let mut triples = super::triples(ntriples.as_bytes());
+        let triple = triples.next().unwrap().unwrap();
+        assert!(matches!(triple.object, Node::Literal(Literal {
+            literal_tag: LiteralTag::LangTag(ref lang_tag), ..
+        }) if lang_tag == "en-US"));
+        let error = triples.next().unwrap().err().unwrap();
+        assert_eq!(error.to_string(), "Invalid language tag 123 at line 2, column 54");
+    }
+
+    #[test]
+    fn rejects_invalid_iris() {
+        let lines = [
+            "<foo> <http://example.org/p> <http://example.org/o> .",
+            "<http://example.org/s> <#p> <http://example.org/o> .",
+            "<http://example.org/s> <http://example.org/p> <../o> .",
+            "<http://example.org/s> <http://example.org/p> <http://example.org/\\u0020> .",
+        ];
+        for line in lines {
+            assert!(super::read(&mut line.as_bytes()).is_err(), "{}", line);
+        }
+        let error = super::read(&mut lines[0].as_bytes()).err().unwrap();
+        assert_eq!(error.to_string(), "Relative IRI <foo> at line 1, column 6");
+    }
+}
@@ -0,0 +1,26 @@
+use crate::error::PenyuError;
+use crate::model::graph::MemoryGraph;
+use crate::model::iri::Iri;
+use crate::model::triple::Triple;
+
+/// Receives what a reader parses, as it is parsed.
+pub trait TripleSink {
+    fn add_triple(&mut self, triple: Triple) -> Result<(), PenyuError>;
+    fn add_prefix(&mut self, _prefix: String, _iri: Iri) -> Result<(), PenyuError> { Ok(()) }
+    fn set_base_ns(&mut self, _base_ns: Iri) -> Result<(), PenyuError> { Ok(()) }
+}
+
+impl TripleSink for MemoryGraph {
+    fn add_triple(&mut self, triple: Triple) -> Result<(), PenyuError> {
+        MemoryGraph::add_triple(self, triple);
+        Ok(())
+    }
+    fn add_prefix(&mut self, prefix: String, iri: Iri) -> Result<(), PenyuError> {
+        MemoryGraph::add_prefix(self, prefix, iri);
+        Ok(())
+    }
+    fn set_base_ns(&mut self, base_ns: Iri) -> Result<(), PenyuError> {
+        MemoryGraph::set_base_ns(self, base_ns);
+        Ok(())
+    }
+}
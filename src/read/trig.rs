@@ -0,0 +1,36 @@
+use crate::error::PenyuError;
+use crate::model::dataset::MemoryDataset;
+use crate::read::turtle;
+use std::io::Read;
+
+pub fn read<R: Read>(read: &mut R) -> Result<MemoryDataset, PenyuError> {
+    turtle::read_dataset(read, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::dataset::Dataset;
+    use crate::model::iri::Iri;
+    use crate::model::node::Entity;
+
+    #[test]
+    fn reads_graphs() {
+        let trig = "PREFIX ex: <http://example.org/>\n\
+                    ex:a ex:p ex:b .\n\
+                    ex:g1 { ex:a ex:p ex:c . ex:c ex:q ( 1 2 ) }\n\
+                    GRAPH _:g2 { [ ex:p ex:d ] . }\n\
+                    { ex:a ex:p ex:e }\n";
+        let dataset = super::read(&mut trig.as_bytes()).unwrap();
+        assert_eq!(dataset.len(), 9);
+        assert_eq!(dataset.quads_matching(None, None, None, Some(None)).count(), 2);
+        let g1 = Entity::from(Iri::from("http://example.org/g1"));
+        assert_eq!(dataset.quads_matching(None, None, None, Some(Some(&g1))).count(), 6);
+        assert_eq!(dataset.graph_names().count(), 2);
+        assert!(super::read(&mut "ex:g { }".as_bytes()).is_err());
+        let mut bytes: Vec<u8> = Vec::new();
+        crate::write::trig::write(&mut bytes, &dataset).unwrap();
+        let dataset2 = super::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(dataset2.len(), dataset.len());
+        assert_eq!(dataset2.graph_names().count(), 2);
+    }
+}
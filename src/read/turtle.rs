@@ -0,0 +1,827 @@
+use crate::error::PenyuError;
+use crate::model::dataset::{Dataset, MemoryDataset};
+use crate::model::graph::MemoryGraph;
+use crate::model::iri::Iri;
+use crate::model::literal::{Literal, LiteralTag};
+use crate::model::node::{BlankNode, Entity, Node};
+use crate::read::blank_nodes::BlankNodes;
+use crate::syntax;
+use crate::vocabs::{rdf, xsd};
+use std::io::Read;
+
+pub fn read<R: Read>(read: &mut R) -> Result<MemoryGraph, PenyuError> {
+    Ok(read_dataset(read, false)?.into_default_graph())
+}
+
+/// Reads Turtle, or TriG if `is_trig`, which adds graph blocks to the Turtle syntax.
+pub(crate) fn read_dataset<R: Read>(read: &mut R, is_trig: bool)
+                                    -> Result<MemoryDataset, PenyuError> {
+    let mut input = String::new();
+    read.read_to_string(&mut input)?;
+    let mut parser = Parser::new(&input, is_trig);
+    parser.parse_document()?;
+    Ok(parser.dataset)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    line: usize,
+    column: usize,
+    is_trig: bool,
+    dataset: MemoryDataset,
+    graph_name: Option<Entity>,
+    blank_nodes: BlankNodes,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str, is_trig: bool) -> Parser<'a> {
+        Parser {
+            input,
+            pos: 0,
+            line: 1,
+            column: 1,
+            is_trig,
+            dataset: MemoryDataset::new(),
+            graph_name: None,
+            blank_nodes: BlankNodes::new("b"),
+        }
+    }
+
+    fn add<S, P, O>(&mut self, subject: S, predicate: P, object: O)
+    where
+        S: Into<Entity>,
+        P: Into<Iri>,
+        O: Into<Node>,
+    {
+        self.dataset.add(subject, predicate, object, self.graph_name.clone());
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, PenyuError> {
+        Err(PenyuError::from(
+            format!("{} at line {}, column {}", message, self.line, self.column)
+        ))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+    fn peek_at(&self, n: usize) -> Option<char> {
+        self.input[self.pos..].chars().nth(n)
+    }
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+    fn next_or_err(&mut self) -> Result<char, PenyuError> {
+        match self.next() {
+            Some(c) => { Ok(c) }
+            None => { self.error("Unexpected end of input".to_string()) }
+        }
+    }
+    fn starts_with(&self, prefix: &str) -> bool {
+        self.input[self.pos..].starts_with(prefix)
+    }
+    fn skip(&mut self, n: usize) {
+        for _ in 0..n {
+            self.next();
+        }
+    }
+    fn expect(&mut self, expected: char) -> Result<(), PenyuError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.next();
+                Ok(())
+            }
+            Some(c) => { self.error(format!("Expected '{}', but found '{}'", expected, c)) }
+            None => { self.error(format!("Expected '{}', but found end of input", expected)) }
+        }
+    }
+    fn skip_ws(&mut self) {
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' | '\r' | '\n' => { self.next(); }
+                '#' => {
+                    while let Some(c) = self.next() {
+                        if c == '\n' { break; }
+                    }
+                }
+                _ => { break; }
+            }
+        }
+    }
+    fn is_keyword(&self, keyword: &str, case_sensitive: bool) -> bool {
+        let rest = &self.input[self.pos..];
+        let Some(head) = rest.get(..keyword.len()) else { return false; };
+        let matches =
+            if case_sensitive { head == keyword } else { head.eq_ignore_ascii_case(keyword) };
+        let mut chars = rest[keyword.len()..].chars();
+        let continues_name = |c: char| syntax::is_pn_chars(c) || c == ':';
+        matches && match chars.next() {
+            Some('.') => { !chars.next().is_some_and(continues_name) }
+            Some(c) => { !continues_name(c) }
+            None => { true }
+        }
+    }
+
+    fn parse_document(&mut self) -> Result<(), PenyuError> {
+        loop {
+            self.skip_ws();
+            if self.peek().is_none() {
+                return Ok(());
+            }
+            self.parse_statement()?;
+        }
+    }
+
+    fn parse_statement(&mut self) -> Result<(), PenyuError> {
+        if self.starts_with("@prefix") {
+            self.skip("@prefix".len());
+            self.parse_prefix_rest()?;
+            self.skip_ws();
+            self.expect('.')
+        } else if self.starts_with("@base") {
+            self.skip("@base".len());
+            self.parse_base_rest()?;
+            self.skip_ws();
+            self.expect('.')
+        } else if self.is_keyword("PREFIX", false) {
+            self.skip("PREFIX".len());
+            self.parse_prefix_rest()
+        } else if self.is_keyword("BASE", false) {
+            self.skip("BASE".len());
+            self.parse_base_rest()
+        } else if self.is_trig {
+            self.parse_block()
+        } else {
+            self.parse_triples()?;
+            self.skip_ws();
+            self.expect('.')
+        }
+    }
+
+    fn parse_block(&mut self) -> Result<(), PenyuError> {
+        if self.is_keyword("GRAPH", false) {
+            self.skip("GRAPH".len());
+            self.skip_ws();
+            let graph_name = self.parse_graph_label()?;
+            self.skip_ws();
+            return self.parse_wrapped_graph(Some(graph_name));
+        }
+        match self.peek() {
+            Some('{') => { self.parse_wrapped_graph(None) }
+            Some('[') | Some('(') => {
+                self.parse_triples()?;
+                self.skip_ws();
+                self.expect('.')
+            }
+            _ => {
+                let label = self.parse_graph_label()?;
+                self.skip_ws();
+                if self.peek() == Some('{') {
+                    self.parse_wrapped_graph(Some(label))
+                } else {
+                    self.parse_predicate_object_list(&label)?;
+                    self.skip_ws();
+                    self.expect('.')
+                }
+            }
+        }
+    }
+
+    fn parse_graph_label(&mut self) -> Result<Entity, PenyuError> {
+        match self.peek() {
+            Some('_') if self.peek_at(1) == Some(':') => {
+                Ok(Entity::from(self.parse_blank_node_label()?))
+            }
+            _ => { Ok(Entity::from(self.parse_iri()?)) }
+        }
+    }
+
+    fn parse_wrapped_graph(&mut self, graph_name: Option<Entity>) -> Result<(), PenyuError> {
+        self.expect('{')?;
+        self.graph_name = graph_name;
+        loop {
+            self.skip_ws();
+            if self.peek() == Some('}') {
+                break;
+            }
+            self.parse_triples()?;
+            self.skip_ws();
+            if self.peek() == Some('.') {
+                self.next();
+            } else {
+                break;
+            }
+        }
+        self.skip_ws();
+        self.expect('}')?;
+        self.graph_name = None;
+        Ok(())
+    }
+
+    fn parse_prefix_rest(&mut self) -> Result<(), PenyuError> {
+        self.skip_ws();
+        let prefix = self.parse_pn_prefix()?;
+        self.expect(':')?;
+        self.skip_ws();
+        let iri = self.parse_iri_ref()?;
+        let iri = self.resolve(iri);
+        self.dataset.add_prefix(prefix, iri);
+        Ok(())
+    }
+
+    fn parse_base_rest(&mut self) -> Result<(), PenyuError> {
+        self.skip_ws();
+        let iri = self.parse_iri_ref()?;
+        let iri = self.resolve(iri);
+        self.dataset.set_base_ns(iri);
+        Ok(())
+    }
+
+    fn resolve(&self, iri: String) -> Iri {
+        match self.dataset.base_ns() {
+            Some(base) => { base.resolve(&iri) }
+            None => { Iri::from(iri) }
+        }
+    }
+
+    fn parse_triples(&mut self) -> Result<(), PenyuError> {
+        if self.peek() == Some('[') {
+            let (subject, is_anon) = self.parse_bracketed_blank_node()?;
+            self.skip_ws();
+            // Only a non-empty blank node property list may stand on its own.
+            if self.peek() != Some('.') || is_anon {
+                self.parse_predicate_object_list(&subject)?;
+            }
+            Ok(())
+        } else {
+            let subject = self.parse_subject()?;
+            self.skip_ws();
+            self.parse_predicate_object_list(&subject)
+        }
+    }
+
+    fn parse_subject(&mut self) -> Result<Entity, PenyuError> {
+        match self.peek() {
+            Some('_') if self.peek_at(1) == Some(':') => {
+                Ok(Entity::from(self.parse_blank_node_label()?))
+            }
+            Some('(') => {
+                match self.parse_collection()? {
+                    Node::Entity(entity) => { Ok(entity) }
+                    Node::Literal(_) => { self.error("Literal as subject".to_string()) }
+                }
+            }
+            Some('"') | Some('\'') => {
+                self.error("Literals are not allowed as subjects".to_string())
+            }
+            _ => { Ok(Entity::from(self.parse_iri()?)) }
+        }
+    }
+
+    fn parse_predicate_object_list(&mut self, subject: &Entity) -> Result<(), PenyuError> {
+        self.parse_verb_object_list(subject)?;
+        loop {
+            self.skip_ws();
+            if self.peek() != Some(';') {
+                return Ok(());
+            }
+            self.next();
+            self.skip_ws();
+            match self.peek() {
+                Some(';') | Some('.') | Some(']') | Some('}') | None => {}
+                _ => { self.parse_verb_object_list(subject)?; }
+            }
+        }
+    }
+
+    fn parse_verb_object_list(&mut self, subject: &Entity) -> Result<(), PenyuError> {
+        let predicate = self.parse_verb()?;
+        loop {
+            self.skip_ws();
+            let object = self.parse_object()?;
+            self.add(subject, &predicate, object);
+            self.skip_ws();
+            if self.peek() != Some(',') {
+                return Ok(());
+            }
+            self.next();
+        }
+    }
+
+    fn parse_verb(&mut self) -> Result<Iri, PenyuError> {
+        if self.is_keyword("a", true) {
+            self.next();
+            Ok(rdf::TYPE.clone())
+        } else {
+            self.parse_iri()
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Node, PenyuError> {
+        match self.peek() {
+            Some('<') => { Ok(Node::from(self.parse_iri()?)) }
+            Some('_') if self.peek_at(1) == Some(':') => {
+                Ok(Node::from(Entity::from(self.parse_blank_node_label()?)))
+            }
+            Some('[') => { Ok(Node::from(self.parse_blank_node_property_list()?)) }
+            Some('(') => { self.parse_collection() }
+            Some('"') | Some('\'') => { Ok(Node::from(self.parse_rdf_literal()?)) }
+            Some(c) if c.is_ascii_digit() || c == '+' || c == '-' || c == '.' => {
+                Ok(Node::from(self.parse_numeric_literal()?))
+            }
+            Some(_) if self.is_keyword("true", true) || self.is_keyword("false", true) => {
+                let string = if self.starts_with("true") { "true" } else { "false" };
+                self.skip(string.len());
+                Ok(Node::from(Literal::new(string.to_string(),
+                                           LiteralTag::Type(xsd::BOOLEAN.clone()))))
+            }
+            Some(_) => { Ok(Node::from(self.parse_prefixed_name()?)) }
+            None => { self.error("Expected object, but found end of input".to_string()) }
+        }
+    }
+
+    fn parse_blank_node_property_list(&mut self) -> Result<Entity, PenyuError> {
+        Ok(self.parse_bracketed_blank_node()?.0)
+    }
+
+    /// A blank node property list, or `[]`, in which case the flag is true.
+    fn parse_bracketed_blank_node(&mut self) -> Result<(Entity, bool), PenyuError> {
+        self.expect('[')?;
+        self.skip_ws();
+        let blank_node = Entity::from(self.blank_nodes.fresh());
+        let is_anon = self.peek() == Some(']');
+        if !is_anon {
+            self.parse_predicate_object_list(&blank_node)?;
+            self.skip_ws();
+        }
+        self.expect(']')?;
+        Ok((blank_node, is_anon))
+    }
+
+    fn parse_collection(&mut self) -> Result<Node, PenyuError> {
+        self.expect('(')?;
+        let mut items: Vec<Node> = Vec::new();
+        loop {
+            self.skip_ws();
+            if self.peek() == Some(')') {
+                self.next();
+                break;
+            }
+            items.push(self.parse_object()?);
+        }
+        let mut list = Node::from(rdf::NIL);
+        for item in items.into_iter().rev() {
+            let cell = Entity::from(self.blank_nodes.fresh());
+            self.add(&cell, rdf::FIRST, item);
+            self.add(&cell, rdf::REST, list);
+            list = Node::from(cell);
+        }
+        Ok(list)
+    }
+
+    fn parse_iri(&mut self) -> Result<Iri, PenyuError> {
+        if self.peek() == Some('<') {
+            let iri = self.parse_iri_ref()?;
+            Ok(self.resolve(iri))
+        } else {
+            self.parse_prefixed_name()
+        }
+    }
+
+    fn parse_iri_ref(&mut self) -> Result<String, PenyuError> {
+        self.expect('<')?;
+        let mut iri = String::new();
+        loop {
+            match self.next_or_err()? {
+                '>' => { return Ok(iri); }
+                '\\' => {
+                    let c =
+                        match self.next_or_err()? {
+                            'u' => { self.parse_hex_char(4)? }
+                            'U' => { self.parse_hex_char(8)? }
+                            c => {
+                                return self.error(format!("Invalid escape '\\{}' in IRI", c));
+                            }
+                        };
+                    if !syntax::is_valid_iri_char(c) {
+                        return self.error(format!("Invalid escaped character {:?} in IRI", c));
+                    }
+                    iri.push(c);
+                }
+                c if !syntax::is_valid_iri_char(c) => {
+                    return self.error(format!("Invalid character {:?} in IRI", c));
+                }
+                c => { iri.push(c); }
+            }
+        }
+    }
+
+    fn parse_hex_char(&mut self, len: usize) -> Result<char, PenyuError> {
+        let mut code: u32 = 0;
+        for _ in 0..len {
+            let c = self.next_or_err()?;
+            match c.to_digit(16) {
+                Some(digit) => { code = code * 16 + digit; }
+                None => { return self.error(format!("Invalid hex digit '{}'", c)); }
+            }
+        }
+        match char::from_u32(code) {
+            Some(c) => { Ok(c) }
+            None => { self.error(format!("Invalid code point {:X}", code)) }
+        }
+    }
+
+    fn parse_pn_prefix(&mut self) -> Result<String, PenyuError> {
+        let mut prefix = String::new();
+        if self.peek().is_some_and(syntax::is_pn_chars_base) {
+            prefix.push(self.next_or_err()?);
+            while let Some(c) = self.peek() {
+                if syntax::is_pn_chars(c) || (c == '.' && self.continues_name_after_dots()) {
+                    prefix.push(c);
+                    self.next();
+                } else {
+                    break;
+                }
+            }
+        }
+        Ok(prefix)
+    }
+
+    fn continues_name_after_dots(&self) -> bool {
+        let mut chars = self.input[self.pos..].chars().skip_while(|c| *c == '.');
+        chars.next().is_some_and(|c| {
+            syntax::is_pn_chars(c) || c == ':' || c == '%' || c == '\\'
+        })
+    }
+
+    fn parse_prefixed_name(&mut self) -> Result<Iri, PenyuError> {
+        let prefix = self.parse_pn_prefix()?;
+        if self.peek() != Some(':') {
+            return match self.peek() {
+                Some(c) => { self.error(format!("Unexpected character '{}'", c)) }
+                None => { self.error("Unexpected end of input".to_string()) }
+            };
+        }
+        let Some(namespace) = self.dataset.prefixes().get(&prefix).cloned() else {
+            return self.error(format!("Undefined prefix '{}:'", prefix));
+        };
+        self.next();
+        let local = self.parse_pn_local()?;
+        Ok(namespace.append(local))
+    }
+
+    fn parse_pn_local(&mut self) -> Result<String, PenyuError> {
+        let mut local = String::new();
+        let mut is_first = true;
+        while let Some(c) = self.peek() {
+            match c {
+                '%' => {
+                    self.next();
+                    local.push('%');
+                    for _ in 0..2 {
+                        match self.next_or_err()? {
+                            c if c.is_ascii_hexdigit() => { local.push(c); }
+                            c => { return self.error(format!("Invalid hex digit '{}'", c)); }
+                        }
+                    }
+                }
+                '\\' => {
+                    self.next();
+                    self.parse_local_escape(&mut local)?;
+                }
+                '.' if !is_first && self.continues_name_after_dots() => {
+                    self.next();
+                    local.push('.');
+                }
+                c if syntax::is_pn_chars_u(c) || c == ':' || c.is_ascii_digit()
+                    || (!is_first && syntax::is_pn_chars(c)) => {
+                    self.next();
+                    local.push(c);
+                }
+                _ => { break; }
+            }
+            is_first = false;
+        }
+        Ok(local)
+    }
+
+    fn parse_local_escape(&mut self, local: &mut String) -> Result<(), PenyuError> {
+        let c = self.next_or_err()?;
+        if syntax::is_pn_local_esc(c) {
+            local.push(c);
+            Ok(())
+        } else {
+            self.error(format!("Invalid escape '\\{}' in local name", c))
+        }
+    }
+
+    fn parse_blank_node_label(&mut self) -> Result<BlankNode, PenyuError> {
+        self.skip(2);
+        let mut label = String::new();
+        match self.peek() {
+            Some(c) if syntax::is_pn_chars_u(c) || c.is_ascii_digit() => {
+                label.push(c);
+                self.next();
+            }
+            _ => { return self.error("Invalid blank node label".to_string()); }
+        }
+        while let Some(c) = self.peek() {
+            if syntax::is_pn_chars(c) || (c == '.' && self.continues_label_after_dots()) {
+                label.push(c);
+                self.next();
+            } else {
+                break;
+            }
+        }
+        Ok(self.blank_nodes.labeled(label))
+    }
+
+    fn continues_label_after_dots(&self) -> bool {
+        let mut chars = self.input[self.pos..].chars().skip_while(|c| *c == '.');
+        chars.next().is_some_and(syntax::is_pn_chars)
+    }
+
+    fn parse_rdf_literal(&mut self) -> Result<Literal, PenyuError> {
+        let string = self.parse_string()?;
+        if self.peek() == Some('@') {
+            self.next();
+            let lang_tag = self.parse_lang_tag()?;
+            match LiteralTag::lang(&lang_tag) {
+                Ok(tag) => { Ok(Literal::new(string, tag)) }
+                Err(error) => { self.error(error.to_string()) }
+            }
+        } else if self.starts_with("^^") {
+            self.skip(2);
+            let datatype = self.parse_iri()?;
+            Ok(Literal::new(string, LiteralTag::Type(datatype)))
+        } else {
+            Ok(Literal::from(string))
+        }
+    }
+
+    fn parse_lang_tag(&mut self) -> Result<String, PenyuError> {
+        let mut lang_tag = String::new();
+        while let Some(c) = self.peek() {
+            let is_part =
+                c.is_ascii_alphabetic() || (!lang_tag.is_empty() && c.is_ascii_digit())
+                    || (c == '-' && !lang_tag.is_empty()
+                    && self.peek_at(1).is_some_and(|c| c.is_ascii_alphanumeric()));
+            if is_part {
+                lang_tag.push(c);
+                self.next();
+            } else {
+                break;
+            }
+        }
+        if lang_tag.is_empty() {
+            self.error("Empty language tag".to_string())
+        } else {
+            Ok(lang_tag)
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, PenyuError> {
+        let quote = self.next_or_err()?;
+        let is_long = self.peek() == Some(quote) && self.peek_at(1) == Some(quote);
+        if is_long {
+            self.skip(2);
+        }
+        let mut string = String::new();
+        loop {
+            if !is_long && matches!(self.peek(), Some('\n' | '\r')) {
+                return self.error("Line break in short string".to_string());
+            }
+            match self.next_or_err()? {
+                c if c == quote => {
+                    if !is_long {
+                        return Ok(string);
+                    } else if self.peek() == Some(quote) && self.peek_at(1) == Some(quote) {
+                        self.skip(2);
+                        return Ok(string);
+                    } else {
+                        string.push(c);
+                    }
+                }
+                '\\' => { string.push(self.parse_string_escape()?); }
+                c => { string.push(c); }
+            }
+        }
+    }
+
+    fn parse_string_escape(&mut self) -> Result<char, PenyuError> {
+        match self.next_or_err()? {
+            't' => { Ok('\t') }
+            'b' => { Ok('\x08') }
+            'n' => { Ok('\n') }
+            'r' => { Ok('\r') }
+            'f' => { Ok('\x0C') }
+            '"' => { Ok('"') }
+            '\'' => { Ok('\'') }
+            '\\' => { Ok('\\') }
+            'u' => { self.parse_hex_char(4) }
+            'U' => { self.parse_hex_char(8) }
+            c => { self.error(format!("Invalid escape '\\{}' in string", c)) }
+        }
+    }
+
+    fn parse_numeric_literal(&mut self) -> Result<Literal, PenyuError> {
+        let mut string = String::new();
+        if let Some(c) = self.peek().filter(|c| *c == '+' || *c == '-') {
+            string.push(c);
+            self.next();
+        }
+        let n_integer_digits = self.push_digits(&mut string);
+        let mut datatype = xsd::INTEGER;
+        if self.peek() == Some('.') && self.peek_at(1).is_some_and(|c| c.is_ascii_digit()) {
+            string.push('.');
+            self.next();
+            self.push_digits(&mut string);
+            datatype = xsd::DECIMAL;
+        } else if self.peek() == Some('.') && n_integer_digits > 0 && self.is_exponent_at(1) {
+            string.push('.');
+            self.next();
+        } else if n_integer_digits == 0 {
+            return self.error("Invalid numeric literal".to_string());
+        }
+        if self.is_exponent_at(0) {
+            string.push(self.next_or_err()?);
+            if let Some(c) = self.peek().filter(|c| *c == '+' || *c == '-') {
+                string.push(c);
+                self.next();
+            }
+            self.push_digits(&mut string);
+            datatype = xsd::DOUBLE;
+        }
+        Ok(Literal::new(string, LiteralTag::Type(datatype.clone())))
+    }
+
+    fn push_digits(&mut self, string: &mut String) -> usize {
+        let mut n_digits: usize = 0;
+        while let Some(c) = self.peek().filter(|c| c.is_ascii_digit()) {
+            string.push(c);
+            self.next();
+            n_digits += 1;
+        }
+        n_digits
+    }
+
+    fn is_exponent_at(&self, n: usize) -> bool {
+        match (self.peek_at(n), self.peek_at(n + 1), self.peek_at(n + 2)) {
+            (Some('e' | 'E'), Some('+' | '-'), Some(c)) => { c.is_ascii_digit() }
+            (Some('e' | 'E'), Some(c), _) => { c.is_ascii_digit() }
+            _ => { false }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::graph::{Graph, MemoryGraph};
+    use crate::model::iri::Iri;
+    use crate::model::literal::{Literal, LiteralTag};
+    use crate::model::node::{Entity, Node};
+    use crate::model::triple::Triple;
+    use crate::vocabs::{obo, rdf, rdfs, xsd};
+
+    fn read_str(string: &str) -> MemoryGraph {
+        super::read(&mut string.as_bytes()).unwrap()
+    }
+
+    fn objects(graph: &MemoryGraph, subject: &Entity, predicate: &Iri) -> Vec<Node> {
+        graph.triples_matching(Some(subject), Some(predicate), None)
+            .map(|triple| triple.object).collect()
+    }
+
+    #[test]
+    fn reads_directives_and_terms() {
+        let graph = read_str(r#"
+            @prefix obo: <http://purl.obolibrary.org/obo/> .
+            PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+            @base <http://example.org/> .
+            # An organ
+            obo:UBERON_0002107 a obo:UBERON_0000062 ;
+                rdfs:label "liver"@en, 'jecur'@la ;
+                rdfs:comment """An organ
+with "lobes".""" ;
+                obo:count 3, -1.5, 2.0e3, true ;
+                obo:id\-x "x\tYé" ;
+                .
+        "#);
+        assert_eq!(graph.prefixes().len(), 2);
+        assert!(graph.base_ns().as_ref().is_some_and(|iri| iri.same_as("http://example.org/")));
+        assert_eq!(graph.len(), 9);
+        let liver = Entity::from(obo::ns::UBERON.append("0002107".to_string()));
+        assert!(objects(&graph, &liver, rdf::TYPE)
+            == vec![Node::from(obo::ns::UBERON.append("0000062".to_string()))]);
+        assert!(objects(&graph, &liver, rdfs::COMMENT)
+            == vec![Node::from(Literal::from("An organ\nwith \"lobes\".".to_string()))]);
+        assert!(objects(&graph, &liver, &obo::NAMESPACE.append("id-x".to_string()))
+            == vec![Node::from(Literal::from("x\tY\u{E9}".to_string()))]);
+        let numbers: Vec<(String, Iri)> =
+            objects(&graph, &liver, &obo::NAMESPACE.append("count".to_string())).into_iter()
+                .filter_map(|node| match node {
+                    Node::Literal(Literal { string, literal_tag: LiteralTag::Type(iri) }) => {
+                        Some((string, iri))
+                    }
+                    _ => None
+                }).collect();
+        assert!(numbers == vec![
+            ("-1.5".to_string(), xsd::DECIMAL.clone()),
+            ("2.0e3".to_string(), xsd::DOUBLE.clone()),
+            ("3".to_string(), xsd::INTEGER.clone()),
+            ("true".to_string(), xsd::BOOLEAN.clone()),
+        ]);
+    }
+
+    #[test]
+    fn reads_blank_nodes_and_collections() {
+        let graph = read_str(r#"
+            @prefix : <http://example.org/> .
+            :a :p [ :q :b ; :r ( 1 _:x [] ) ] .
+            [ :q :c ] .
+            _:x :q () .
+        "#);
+        let a = Entity::from(Iri::from("http://example.org/a"));
+        let p = Iri::from("http://example.org/p");
+        assert_eq!(graph.len(), 11);
+        let Some(Node::Entity(inner)) = objects(&graph, &a, &p).pop() else { panic!() };
+        let r = Iri::from("http://example.org/r");
+        let Some(Node::Entity(list)) = objects(&graph, &inner, &r).pop() else { panic!() };
+        assert_eq!(objects(&graph, &list, rdf::FIRST).len(), 1);
+        let x = Triple::create(Entity::from(crate::model::node::BlankNode::from("x")),
+                               Iri::from("http://example.org/q"), rdf::NIL);
+        assert!(graph.contains(&x));
+    }
+
+    #[test]
+    fn reports_position() {
+        let error =
+            super::read(&mut "@prefix : <http://example.org/> .\n:a :b :c ;\n  :d \"e .\n"
+                .as_bytes()).err().unwrap();
+        assert_eq!(error.to_string(), "Line break in short string at line 3, column 10");
+        let error =
+            super::read(&mut "<a> <b> x:c .".as_bytes()).err().unwrap();
+        assert_eq!(error.to_string(), "Undefined prefix 'x:' at line 1, column 10");
+    }
+
+    #[test]
+    fn rejects_invalid_statements() {
+        let prefix = "@prefix : <http://example.org/> .\n";
+        let statements =
+            [r":a :b\u0041 :c .", r":a :b\tc :d .", "[] .", "[ ] .", ":a :b \"c\"@en-x .",
+             r"<http://example.org/\u0020> :b :c .", r":a :b <http://example.org/\U0000003C> ."];
+        for statement in statements {
+            let turtle = format!("{}{}", prefix, statement);
+            assert!(super::read(&mut turtle.as_bytes()).is_err(), "{}", statement);
+        }
+        let graph = read_str(&format!("{}[] :p :o .\n[ :p :o ] .\n:a\\~b :p :o .", prefix));
+        assert_eq!(graph.len(), 3);
+        assert!(graph.contains(&Triple::create(Iri::from("http://example.org/a~b"),
+                                               Iri::from("http://example.org/p"),
+                                               Iri::from("http://example.org/o"))));
+    }
+
+    #[test]
+    fn round_trips() {
+        let turtle = "PREFIX obo: <http://purl.obolibrary.org/obo/>\n\
+                      PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>\n\
+                      obo:UBERON_0002107 rdfs:label \"jecur\"@la, \"liver\" .\n";
+        let graph = read_str(turtle);
+        let mut bytes: Vec<u8> = Vec::new();
+        crate::write::turtle::write(&mut bytes, &graph).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), turtle);
+    }
+
+    #[test]
+    fn resolves_against_base() {
+        let turtle = "BASE <http://example.org/a/b>\n\
+                      PREFIX ex: <../ns#>\n\
+                      <c> ex:p <#d>, </e> .\n\
+                      BASE <f/>\n\
+                      <g> ex:p <http://other.org/h> .\n";
+        let graph = read_str(turtle);
+        let mut bytes: Vec<u8> = Vec::new();
+        crate::write::ntriples::write(&mut bytes, &graph).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(),
+                   "<http://example.org/a/c> <http://example.org/ns#p> \
+                    <http://example.org/a/b#d> .\n\
+                    <http://example.org/a/c> <http://example.org/ns#p> <http://example.org/e> .\n\
+                    <http://example.org/a/f/g> <http://example.org/ns#p> \
+                    <http://other.org/h> .\n");
+        let mut bytes: Vec<u8> = Vec::new();
+        crate::write::turtle::write(&mut bytes, &graph).unwrap();
+        let turtle = String::from_utf8(bytes).unwrap();
+        assert!(turtle.contains("<../c> ex:p <../b#d>, </e> ."), "{}", turtle);
+        assert!(turtle.contains("<g> ex:p <http://other.org/h> ."), "{}", turtle);
+        assert!(read_str(&turtle).len() == graph.len());
+    }
+}
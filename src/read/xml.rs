@@ -1,323 +1,599 @@
 use crate::error::PenyuError;
-use crate::model::graph::{Graph, MemoryGraph};
+use crate::model::graph::MemoryGraph;
 use crate::model::iri::Iri;
 use crate::model::literal::{Literal, LiteralTag};
-use crate::model::node::{BlankNode, Entity, Node};
-use crate::vocabs;
+use crate::model::node::{Entity, Node};
+use crate::model::triple::Triple;
+use crate::read::blank_nodes::BlankNodes;
+use crate::read::sink::TripleSink;
+use crate::{syntax, vocabs};
 use crate::vocabs::rdf;
+use std::collections::BTreeMap;
 use std::io::Read;
 use xml::attribute::OwnedAttribute;
 use xml::name::OwnedName;
 use xml::namespace::Namespace;
 use xml::reader::XmlEvent;
 
-enum State {
-    PreStart,
-    Started,
-    Rdf { stack: Stack, n_b_nodes: usize },
-    PostRdf,
-    PostEnd,
+enum Frame {
+    Rdf,
+    Node { subject: Entity, n_li: usize },
+    Property(Box<PropertyFrame>),
+    Empty,
+    Collection { statement: Box<Statement>, items: Vec<Node> },
+    XmlLiteral(Box<XmlLiteralFrame>),
 }
 
+struct Statement {
+    subject: Entity,
+    predicate: Iri,
+    reification: Option<Iri>,
+}
+
+struct PropertyFrame {
+    statement: Statement,
+    literal_tag: Option<LiteralTag>,
+    text: String,
+    object: Option<Entity>,
+}
+
+struct XmlLiteralFrame {
+    statement: Statement,
+    xml: String,
+    declared: Vec<BTreeMap<String, String>>,
+}
+
+/// The xml:lang and xml:base in effect for an element, inherited from its ancestors unless
+/// the element sets its own.
+#[derive(Clone, Default)]
+struct Scope {
+    lang: Option<String>,
+    base: Option<Iri>,
+}
+
+struct Parser<'a, S: TripleSink> {
+    sink: &'a mut S,
+    prefixes: BTreeMap<String, Iri>,
+    scopes: Vec<Scope>,
+    blank_nodes: BlankNodes,
+    frames: Vec<Frame>,
+    has_root: bool,
+}
 
 pub fn read<R: Read>(read: &mut R) -> Result<MemoryGraph, PenyuError> {
     let mut graph = MemoryGraph::new();
-    let parser = xml::EventReader::new(read);
-    let mut state = State::PreStart;
-    for event in parser {
-        let event = event?;
-        state =
-            match event {
-                XmlEvent::StartDocument { .. } => {
-                    match state {
-                        State::PreStart => { State::Started }
-                        _ => {
-                            Err(PenyuError::from("Unexpected document start"))?
-                        }
-                    }
-                }
-                XmlEvent::ProcessingInstruction { .. } => { state }
-                XmlEvent::StartElement {
-                    name, attributes, namespace
-                } => {
-                    match state {
-                        State::Started => {
-                            parse_rdf_start(&mut graph, &name, &attributes, &namespace)?
-                        }
-                        State::Rdf { stack, mut n_b_nodes } => {
-                            let stack =
-                                parse_rdf(stack, name, &attributes, &mut graph, &mut n_b_nodes)?;
-                            State::Rdf { stack, n_b_nodes }
-                        }
-                        _ => {
-                            Err(PenyuError::from(
-                                format!("Unexpected start tag {:?}", name)
-                            ))?
-                        }
-                    }
+    read_to_sink(read, &mut graph)?;
+    Ok(graph)
+}
+
+/// Like `read`, but resolves relative IRIs against the IRI the document was retrieved from,
+/// unless the document sets its own xml:base.
+pub fn read_with_base<R: Read>(read: &mut R, base: Iri) -> Result<MemoryGraph, PenyuError> {
+    let mut graph = MemoryGraph::new();
+    parse(read, &mut graph, Some(base))?;
+    Ok(graph)
+}
+
+/// Like `read`, but passes each triple, prefix and base to the sink as soon as it is parsed,
+/// instead of collecting them in a graph.
+pub fn read_to_sink<R: Read, S: TripleSink>(read: &mut R, sink: &mut S)
+                                            -> Result<(), PenyuError> {
+    parse(read, sink, None)
+}
+
+fn parse<R: Read, S: TripleSink>(read: &mut R, sink: &mut S, base: Option<Iri>)
+                                 -> Result<(), PenyuError> {
+    let document_scope = base.map(|base| Scope { lang: None, base: Some(base) });
+    let mut parser = Parser {
+        sink,
+        prefixes: BTreeMap::new(),
+        scopes: document_scope.into_iter().collect(),
+        blank_nodes: BlankNodes::new("node"),
+        frames: Vec::new(),
+        has_root: false,
+    };
+    for event in xml::EventReader::new(read) {
+        match event? {
+            XmlEvent::StartDocument { .. } => {}
+            XmlEvent::ProcessingInstruction { .. } => {}
+            XmlEvent::StartElement { name, attributes, namespace } => {
+                parser.start_element(name, attributes, namespace)?;
+            }
+            XmlEvent::EndElement { name } => { parser.end_element(&name)?; }
+            XmlEvent::CData(string) => { parser.characters(string, false)?; }
+            XmlEvent::Comment(_) => {}
+            XmlEvent::Characters(string) => { parser.characters(string, false)?; }
+            XmlEvent::Whitespace(string) => { parser.characters(string, true)?; }
+            XmlEvent::EndDocument => {
+                if !parser.has_root || !parser.frames.is_empty() {
+                    Err(PenyuError::from("Unexpected end of document"))?
                 }
-                XmlEvent::EndElement { name } => {
-                    match state {
-                        State::Rdf { stack, n_b_nodes } => {
-                            if stack.is_empty() {
-                                if tag_is_rdf_rdf(&name) {
-                                    State::PostRdf
-                                } else {
-                                    err_unexpected_end_tag(&name)?
-                                }
-                            } else {
-                                let stack = stack.pop()?;
-                                State::Rdf { stack, n_b_nodes }
-                            }
-                        }
-                        _ => {
-                            err_unexpected_end_tag(&name)?
-                        }
-                    }
+            }
+        }
+    }
+    Ok(())
+}
+
+impl<S: TripleSink> Parser<'_, S> {
+    fn add_prefix(&mut self, prefix: String, iri: Iri) -> Result<(), PenyuError> {
+        self.prefixes.insert(prefix.clone(), iri.clone());
+        self.sink.add_prefix(prefix, iri)
+    }
+    fn add<E, P, O>(&mut self, subject: E, predicate: P, object: O) -> Result<(), PenyuError>
+    where
+        E: Into<Entity>,
+        P: Into<Iri>,
+        O: Into<Node>,
+    {
+        self.sink.add_triple(Triple::create(subject, predicate, object))
+    }
+    fn add_statement<O: Into<Node>>(&mut self, statement: &Statement, object: O)
+                                    -> Result<(), PenyuError> {
+        let object = object.into();
+        if let Some(reification) = &statement.reification {
+            self.add(reification, rdf::TYPE, rdf::STATEMENT)?;
+            self.add(reification, rdf::SUBJECT, &statement.subject)?;
+            self.add(reification, rdf::PREDICATE, &statement.predicate)?;
+            self.add(reification, rdf::OBJECT, &object)?;
+        }
+        self.add(&statement.subject, &statement.predicate, object)
+    }
+
+    fn start_element(&mut self, name: OwnedName, attributes: Vec<OwnedAttribute>,
+                     namespace: Namespace) -> Result<(), PenyuError> {
+        let scope = self.scope_for(&attributes);
+        if !self.has_root && scope.base.is_some() {
+            self.sink.set_base_ns(scope.base.clone().unwrap())?;
+        }
+        self.scopes.push(scope);
+        match self.frames.last_mut() {
+            None => {
+                if self.has_root {
+                    Err(PenyuError::from(format!("Unexpected start tag {:?}", name)))?
                 }
-                XmlEvent::CData(string) => {
-                    handle_characters(&mut graph, state, string)?
+                self.has_root = true;
+                for (prefix, ns) in namespace.0.iter() {
+                    self.add_prefix(prefix.clone(), Iri::from(ns.clone()))?;
                 }
-                XmlEvent::Comment(_) => { state }
-                XmlEvent::Characters(string) => {
-                    handle_characters(&mut graph, state, string)?
+                if tag_is_rdf_rdf(&name) {
+                    check_attributes_top_level(&attributes)?;
+                    self.frames.push(Frame::Rdf);
+                } else {
+                    self.start_node_element(&name, &attributes)?;
                 }
-                XmlEvent::Whitespace(_) => { state }
-                XmlEvent::EndDocument => {
-                    if let State::PostRdf = state {
-                        State::PostEnd
-                    } else {
-                        Err(PenyuError::from("Unexpected end of document"))?
-                    }
+            }
+            Some(Frame::Rdf) => { self.start_node_element(&name, &attributes)?; }
+            Some(Frame::Node { .. }) => { self.start_property_element(&name, &attributes)?; }
+            Some(Frame::Property(property)) => {
+                if property.object.is_some() || !is_whitespace(&property.text) {
+                    Err(PenyuError::from(
+                        format!("Property element can only contain a single node, \
+                                 but found {:?}", name)
+                    ))?
                 }
+                let entity = self.start_node_element(&name, &attributes)?;
+                let Some(Frame::Property(property)) = self.frames.iter_mut().rev().nth(1)
+                    else { unreachable!() };
+                property.object = Some(entity.clone());
+                let statement =
+                    Statement {
+                        subject: property.statement.subject.clone(),
+                        predicate: property.statement.predicate.clone(),
+                        reification: property.statement.reification.clone(),
+                    };
+                self.add_statement(&statement, entity)?;
+            }
+            Some(Frame::Collection { .. }) => {
+                let entity = self.start_node_element(&name, &attributes)?;
+                let Some(Frame::Collection { items, .. }) = self.frames.iter_mut().rev().nth(1)
+                    else { unreachable!() };
+                items.push(Node::from(entity));
+            }
+            Some(Frame::Empty) => {
+                Err(PenyuError::from(
+                    format!("Unexpected start tag {:?} in empty property element", name)
+                ))?
             }
+            Some(Frame::XmlLiteral(literal)) => {
+                literal.start_element(&name, &attributes);
+            }
+        }
+        Ok(())
     }
-    Ok(graph)
-}
 
-fn handle_characters(graph: &mut MemoryGraph, state: State, string: String)
-    -> Result<State, PenyuError> {
-    Ok(match state {
-        State::Rdf { stack: Stack::P(Some(stack_p)), n_b_nodes } => {
-            let StackP {
-                stack_s, predicate, literal_tag
-            } = *stack_p;
-            let literal_tag =
-                literal_tag.unwrap_or_else(||
-                    LiteralTag::Type(vocabs::xsd::STRING.clone())
-                );
-            let literal = Literal::new(string, literal_tag);
-            graph.add(&stack_s.subject, &predicate, Node::from(literal));
-            let literal_tag: Option<LiteralTag> = None;
-            let stack_p = StackP::new(stack_s, predicate, literal_tag);
-            State::Rdf { stack: Stack::P(Some(Box::new(stack_p))), n_b_nodes }
-        }
-        _ => {
-            Err(PenyuError::from(
-                format!("Unexpected characters {}", string)
-            ))?
+    fn end_element(&mut self, name: &OwnedName) -> Result<(), PenyuError> {
+        let Some(frame) = self.frames.pop() else {
+            Err(PenyuError::from(format!("Unexpected end tag {:?}", name)))?
+        };
+        self.scopes.pop();
+        match frame {
+            Frame::Rdf | Frame::Node { .. } | Frame::Empty => {}
+            Frame::Property(property) => {
+                let PropertyFrame { statement, literal_tag, text, object } = *property;
+                if object.is_none() {
+                    let literal_tag =
+                        literal_tag.unwrap_or_else(|| {
+                            LiteralTag::Type(vocabs::xsd::STRING.clone())
+                        });
+                    self.add_statement(&statement, Literal::new(text, literal_tag))?;
+                }
+            }
+            Frame::Collection { statement, items } => {
+                let mut list = Node::from(rdf::NIL);
+                let mut cells: Vec<(Entity, Node)> = Vec::new();
+                for item in items.into_iter().rev() {
+                    let cell = Entity::from(self.blank_nodes.fresh());
+                    cells.push((cell.clone(), item));
+                    list = Node::from(cell);
+                }
+                self.add_statement(&statement, list)?;
+                let mut rest = Node::from(rdf::NIL);
+                for (cell, item) in cells {
+                    self.add(&cell, rdf::FIRST, item)?;
+                    self.add(&cell, rdf::REST, rest)?;
+                    rest = Node::from(cell);
+                }
+            }
+            Frame::XmlLiteral(mut literal) => {
+                if literal.declared.is_empty() {
+                    let literal_tag = LiteralTag::Type(rdf::XML_LITERAL.clone());
+                    self.add_statement(&literal.statement, Literal::new(literal.xml, literal_tag))?;
+                } else {
+                    literal.end_element(name);
+                    self.frames.push(Frame::XmlLiteral(literal));
+                }
+            }
         }
-    })
-}
+        Ok(())
+    }
 
-fn err_unexpected_end_tag(name: &OwnedName) -> Result<State, PenyuError> {
-    Err(PenyuError::from(format!("Unexpected end tag {:?}", name)))
-}
+    fn characters(&mut self, string: String, is_whitespace: bool) -> Result<(), PenyuError> {
+        match self.frames.last_mut() {
+            Some(Frame::Property(property)) => {
+                if property.object.is_some() && !is_whitespace {
+                    Err(PenyuError::from(format!("Unexpected characters {}", string)))?
+                }
+                property.text.push_str(&string);
+            }
+            Some(Frame::XmlLiteral(literal)) => {
+                literal.xml.push_str(&syntax::escape_xml_text(&string));
+            }
+            _ => {
+                if !is_whitespace {
+                    Err(PenyuError::from(format!("Unexpected characters {}", string)))?
+                }
+            }
+        }
+        Ok(())
+    }
 
-fn parse_rdf(stack: Stack, name: OwnedName, attributes: &[OwnedAttribute],
-             graph: &mut MemoryGraph, n_b_nodes: &mut usize) -> Result<Stack, PenyuError> {
-    match stack {
-        Stack::S(stack_s) => {
-            let predicate = iri_from_tag(&name, graph)?;
-            let object =
-                iri_from_attribute(attributes, "resource", rdf::NAMESPACE, graph);
-            let literal_tag: Option<LiteralTag> =
-                if let Some(iri) = object {
-                    graph.add(&stack_s.subject, &predicate, Node::from(Entity::from(iri)));
-                    None
-                } else {
-                    let lang_tag =
-                        string_from_attribute(attributes, "lang", rdf::NAMESPACE);
-                    let datatype =
-                        iri_from_attribute(attributes, "datatype", rdf::NAMESPACE,
-                                           graph);
-                    match (lang_tag, datatype) {
-                        (Some(lang), None) => { Some(LiteralTag::LangTag(lang)) }
-                        (None, Some(datatype)) => { Some(LiteralTag::Type(datatype)) }
-                        (None, None) => { None }
-                        (Some(_), Some(_)) => {
+    fn start_node_element(&mut self, name: &OwnedName, attributes: &[OwnedAttribute])
+                          -> Result<Entity, PenyuError> {
+        if has_ns(name, rdf::NAMESPACE) && name.local_name != "Description"
+            && is_syntax_term(&name.local_name) {
+            Err(PenyuError::from(format!("{:?} cannot be used as node element", name)))?
+        }
+        let mut subject: Option<Entity> = None;
+        let mut property_attributes: Vec<&OwnedAttribute> = Vec::new();
+        for attribute in attributes {
+            let id =
+                if has_ns(&attribute.name, rdf::NAMESPACE) {
+                    match attribute.name.local_name.as_str() {
+                        "about" => { Some(Entity::from(self.iri_from_value(&attribute.value))) }
+                        "ID" => { Some(Entity::from(self.iri_from_id(&attribute.value))) }
+                        "nodeID" => {
+                            Some(Entity::from(self.blank_nodes.labeled(attribute.value.clone())))
+                        }
+                        local if is_syntax_term(local) => {
                             Err(PenyuError::from(
-                                format!("Tag {:?} has both lang and datatype attributes",
-                                        name)
+                                format!("Unexpected attribute {:?} on node element",
+                                        attribute.name)
                             ))?
                         }
+                        _ => {
+                            property_attributes.push(attribute);
+                            None
+                        }
                     }
+                } else if is_property_attribute(attribute)? {
+                    property_attributes.push(attribute);
+                    None
+                } else {
+                    None
                 };
-            Ok(Stack::P(Some(Box::new(StackP::new(stack_s, predicate, literal_tag)))))
-        }
-        Stack::P(stack_p) => {
-            let class = iri_from_tag(&name, graph)?;
-            let id =
-                iri_from_attribute(attributes, "about", rdf::NAMESPACE, graph);
-            let entity =
-                match id {
-                    Some(iri) => { Entity::from(iri) }
-                    None => {
-                        let b_node_id = format!("node{}", n_b_nodes);
-                        *n_b_nodes += 1;
-                        Entity::BlankNode(BlankNode::from(b_node_id))
-                    }
-                };
-            graph.add(&entity, rdf::TYPE, class);
-            if let Some(stack_p) = &stack_p {
-                let object = &stack_p.stack_s.subject;
-                let predicate = &stack_p.predicate;
-                graph.add(&entity, predicate, object);
+            if let Some(id) = id {
+                if subject.is_some() {
+                    Err(PenyuError::from(
+                        format!("Node element {:?} has more than one identifier", name)
+                    ))?
+                }
+                subject = Some(id);
             }
-            Ok(Stack::S(StackS::new(stack_p, entity)))
         }
+        let subject = subject.unwrap_or_else(|| Entity::from(self.blank_nodes.fresh()));
+        if !(has_ns(name, rdf::NAMESPACE) && name.local_name == "Description") {
+            let class = self.iri_from_tag(name)?;
+            self.add(&subject, rdf::TYPE, class)?;
+        }
+        self.add_property_attributes(&subject, &property_attributes)?;
+        self.frames.push(Frame::Node { subject: subject.clone(), n_li: 0 });
+        Ok(subject)
     }
-}
 
-fn iri_from_tag(name: &OwnedName, graph: &mut MemoryGraph) -> Result<Iri, PenyuError> {
-    match &name.namespace {
-        None => {
-            Err(PenyuError::from(format!("No namespace for tag: {:?}", name)))
-        }
-        Some(ns) => {
-            let empty_prefix = "".to_string();
-            let prefix = name.prefix.as_ref().unwrap_or(&empty_prefix);
-            let iri_get = graph.prefixes().get(prefix);
-            let ns_iri =
-                match iri_get {
-                    None => {
-                        let ns_iri = Iri::from(ns.clone());
-                        graph.add_prefix(prefix.clone(), ns_iri.clone());
-                        ns_iri
-                    }
-                    Some(ns_iri) => {
-                        if !ns_iri.same_as(ns) {
-                            Err(PenyuError::from(
-                                format!("Prefix {} used for {}, but already bound to {}",
-                                        prefix, ns, ns_iri)
-                            ))?;
+    fn add_property_attributes(&mut self, subject: &Entity, attributes: &[&OwnedAttribute])
+                               -> Result<(), PenyuError> {
+        for attribute in attributes {
+            let predicate = self.iri_from_tag(&attribute.name)?;
+            if predicate == *rdf::TYPE {
+                let class = self.iri_from_value(&attribute.value);
+                self.add(subject, predicate, class)?;
+            } else {
+                let literal =
+                    match self.lang() {
+                        Some(lang) => {
+                            Literal::new(attribute.value.clone(), LiteralTag::lang(&lang)?)
                         }
-                        ns_iri.clone()
-                    }
-                };
-            Ok(ns_iri.append(name.local_name.clone()))
+                        None => { Literal::from(attribute.value.clone()) }
+                    };
+                self.add(subject, predicate, literal)?;
+            }
         }
+        Ok(())
     }
-}
 
-fn iri_from_attribute(attributes: &[OwnedAttribute], attribute_name: &str, attribute_ns: &Iri,
-                      graph: &MemoryGraph)
-                      -> Option<Iri> {
-    let iri: Option<Iri> =
-        string_from_attribute(attributes, attribute_name, attribute_ns)
-            .map(Iri::from)
-            .map(|mut iri| {
-                for prefix in graph.prefixes().values() {
-                    iri = prefix.maybe_use_as_prefix_for(iri);
+    fn start_property_element(&mut self, name: &OwnedName, attributes: &[OwnedAttribute])
+                              -> Result<(), PenyuError> {
+        let Some(Frame::Node { subject, n_li }) = self.frames.last_mut() else { unreachable!() };
+        let subject = subject.clone();
+        let predicate =
+            if has_ns(name, rdf::NAMESPACE) && name.local_name == "li" {
+                *n_li += 1;
+                rdf::NAMESPACE.append(format!("_{}", n_li))
+            } else if has_ns(name, rdf::NAMESPACE) && (is_syntax_term(&name.local_name)
+                || name.local_name == "Description") {
+                Err(PenyuError::from(format!("{:?} cannot be used as property element", name)))?
+            } else {
+                self.iri_from_tag(name)?
+            };
+        let mut reification: Option<Iri> = None;
+        let mut resource: Option<Entity> = None;
+        let mut parse_type: Option<&str> = None;
+        let mut datatype: Option<Iri> = None;
+        let mut property_attributes: Vec<&OwnedAttribute> = Vec::new();
+        for attribute in attributes {
+            if has_ns(&attribute.name, rdf::NAMESPACE) {
+                match attribute.name.local_name.as_str() {
+                    "ID" => { reification = Some(self.iri_from_id(&attribute.value)); }
+                    "resource" => {
+                        resource = Some(Entity::from(self.iri_from_value(&attribute.value)));
+                    }
+                    "nodeID" => {
+                        let blank_node = self.blank_nodes.labeled(attribute.value.clone());
+                        resource = Some(Entity::from(blank_node));
+                    }
+                    "parseType" => { parse_type = Some(attribute.value.as_str()); }
+                    "datatype" => { datatype = Some(self.iri_from_value(&attribute.value)); }
+                    local if is_syntax_term(local) => {
+                        Err(PenyuError::from(
+                            format!("Unexpected attribute {:?} on property element",
+                                    attribute.name)
+                        ))?
+                    }
+                    _ => { property_attributes.push(attribute); }
                 }
-                iri
-            });
-    iri
-}
-
-fn string_from_attribute(attributes: &[OwnedAttribute], attribute_name: &str, attribute_ns: &Iri)
-                         -> Option<String> {
-    let mut value: Option<String> = None;
-    for attribute in attributes {
-        if attribute.name.local_name == attribute_name
-            && has_ns(&attribute.name, attribute_ns) {
-            value = Some(attribute.value.clone());
+            } else if is_property_attribute(attribute)? {
+                property_attributes.push(attribute);
+            }
+        }
+        let statement = Statement { subject, predicate, reification };
+        match parse_type {
+            Some("Resource") => {
+                let object = Entity::from(self.blank_nodes.fresh());
+                self.add_statement(&statement, &object)?;
+                self.frames.push(Frame::Node { subject: object, n_li: 0 });
+            }
+            Some("Collection") => {
+                self.frames.push(Frame::Collection {
+                    statement: Box::new(statement), items: Vec::new(),
+                });
+            }
+            Some(_) => {
+                self.frames.push(Frame::XmlLiteral(Box::new(XmlLiteralFrame {
+                    statement, xml: String::new(), declared: Vec::new(),
+                })));
+            }
+            None => {
+                if resource.is_some() || !property_attributes.is_empty() {
+                    let object =
+                        resource.unwrap_or_else(|| Entity::from(self.blank_nodes.fresh()));
+                    self.add_statement(&statement, &object)?;
+                    self.add_property_attributes(&object, &property_attributes)?;
+                    self.frames.push(Frame::Empty);
+                } else {
+                    let literal_tag =
+                        match (self.lang(), datatype) {
+                            (Some(_), Some(datatype)) | (None, Some(datatype)) => {
+                                Some(LiteralTag::Type(datatype))
+                            }
+                            (Some(lang), None) => { Some(LiteralTag::lang(&lang)?) }
+                            (None, None) => { None }
+                        };
+                    self.frames.push(Frame::Property(Box::new(PropertyFrame {
+                        statement, literal_tag, text: String::new(), object: None,
+                    })));
+                }
+            }
         }
+        Ok(())
     }
-    value
-}
 
-fn parse_rdf_start(graph: &mut MemoryGraph, name: &OwnedName, attributes: &[OwnedAttribute],
-                   namespace: &Namespace)
-                   -> Result<State, PenyuError> {
-    if tag_is_rdf_rdf(name) {
-        for mapping in namespace.0.iter() {
-            let prefix = mapping.0.clone();
-            let ns_iri = Iri::from(mapping.1.clone());
-            graph.add_prefix(prefix, ns_iri);
+    fn iri_from_tag(&mut self, name: &OwnedName) -> Result<Iri, PenyuError> {
+        match &name.namespace {
+            None => {
+                Err(PenyuError::from(format!("No namespace for tag: {:?}", name)))
+            }
+            Some(ns) => {
+                let empty_prefix = "".to_string();
+                let prefix = name.prefix.as_ref().unwrap_or(&empty_prefix);
+                let iri_get = self.prefixes.get(prefix);
+                let ns_iri =
+                    match iri_get {
+                        None => {
+                            let ns_iri = Iri::from(ns.clone());
+                            self.add_prefix(prefix.clone(), ns_iri.clone())?;
+                            ns_iri
+                        }
+                        Some(ns_iri) => {
+                            if !ns_iri.same_as(ns) {
+                                Err(PenyuError::from(
+                                    format!("Prefix {} used for {}, but already bound to {}",
+                                            prefix, ns, ns_iri)
+                                ))?;
+                            }
+                            ns_iri.clone()
+                        }
+                    };
+                Ok(ns_iri.append(name.local_name.clone()))
+            }
         }
-        parse_attributes_top_level(graph, attributes)?;
-        Ok(State::Rdf { stack: Stack::new(), n_b_nodes: 0 })
-    } else {
-        Err(PenyuError::new("Unexpected start element".to_string(), None))?
     }
-}
-
-struct StackS {
-    stack_p: Option<Box<StackP>>,
-    subject: Entity,
-}
-
-struct StackP {
-    stack_s: StackS,
-    predicate: Iri,
-    literal_tag: Option<LiteralTag>,
-}
-
-enum Stack {
-    S(StackS),
-    P(Option<Box<StackP>>),
-}
 
-impl StackS {
-    fn new(stack_p: Option<Box<StackP>>, subject: Entity) -> StackS { StackS { stack_p, subject } }
-}
+    fn lang(&self) -> Option<String> {
+        self.scopes.last().and_then(|scope| scope.lang.clone())
+    }
 
-impl StackP {
-    fn new(stack_s: StackS, predicate: Iri, literal_tag: Option<LiteralTag>) -> StackP {
-        StackP { stack_s, predicate, literal_tag }
+    fn base(&self) -> Option<&Iri> {
+        self.scopes.last().and_then(|scope| scope.base.as_ref())
     }
-}
 
-impl Stack {
-    fn new() -> Stack {
-        Stack::P(None)
+    fn scope_for(&self, attributes: &[OwnedAttribute]) -> Scope {
+        let mut scope = self.scopes.last().cloned().unwrap_or_default();
+        for attribute in attributes {
+            if has_ns(&attribute.name, vocabs::xml::NAMESPACE) {
+                match attribute.name.local_name.as_str() {
+                    "lang" if attribute.value.is_empty() => { scope.lang = None; }
+                    "lang" => { scope.lang = Some(attribute.value.clone()); }
+                    "base" => {
+                        let base =
+                            match &scope.base {
+                                Some(base) => { base.resolve(&attribute.value) }
+                                None => { Iri::from(attribute.value.clone()) }
+                            };
+                        scope.base = Some(base);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        scope
     }
 
-    fn is_empty(&self) -> bool {
-        match self {
-            Stack::S(_) => { false }
-            Stack::P(stack_p) => { stack_p.is_none() }
+    fn iri_from_value(&self, value: &str) -> Iri {
+        let mut iri =
+            match self.base() {
+                Some(base) => { base.resolve(value) }
+                None => { Iri::from(value.to_string()) }
+            };
+        for prefix in self.prefixes.values() {
+            iri = prefix.maybe_use_as_prefix_for(iri);
         }
+        iri
     }
-    fn pop(self) -> Result<Self, PenyuError> {
-        match self {
-            Stack::S(stack_s) => {
-                Ok(Stack::P(stack_s.stack_p))
-            }
-            Stack::P(Some(stack_p)) => {
-                Ok(Stack::S(stack_p.stack_s))
+
+    fn iri_from_id(&self, id: &str) -> Iri {
+        self.iri_from_value(&format!("#{}", id))
+    }
+}
+
+impl XmlLiteralFrame {
+    fn start_element(&mut self, name: &OwnedName, attributes: &[OwnedAttribute]) {
+        let mut declarations: BTreeMap<String, String> = BTreeMap::new();
+        let names = std::iter::once(name).chain(attributes.iter().map(|attribute| &attribute.name));
+        for name in names {
+            if let Some(ns) = &name.namespace {
+                let prefix = name.prefix.clone().unwrap_or_default();
+                let is_declared =
+                    self.declared.iter().rev()
+                        .find_map(|declared| declared.get(&prefix))
+                        .is_some_and(|declared_ns| declared_ns == ns);
+                if !is_declared {
+                    declarations.insert(prefix, ns.clone());
+                }
             }
-            Stack::P(None) => {
-                Err(PenyuError::new("Stack is empty".to_string(), None))
+        }
+        self.xml.push('<');
+        push_qualified_name(&mut self.xml, name);
+        for (prefix, ns) in &declarations {
+            if prefix.is_empty() {
+                self.xml.push_str(&format!(" xmlns=\"{}\"", syntax::escape_xml_attribute(ns)));
+            } else {
+                let ns = syntax::escape_xml_attribute(ns);
+                self.xml.push_str(&format!(" xmlns:{}=\"{}\"", prefix, ns));
             }
         }
+        let mut attributes: Vec<&OwnedAttribute> = attributes.iter().collect();
+        attributes.sort_by(|attribute1, attribute2| {
+            (&attribute1.name.namespace, &attribute1.name.local_name)
+                .cmp(&(&attribute2.name.namespace, &attribute2.name.local_name))
+        });
+        for attribute in attributes {
+            self.xml.push(' ');
+            push_qualified_name(&mut self.xml, &attribute.name);
+            self.xml.push_str(&format!("=\"{}\"", syntax::escape_xml_attribute(&attribute.value)));
+        }
+        self.xml.push('>');
+        self.declared.push(declarations);
+    }
+    fn end_element(&mut self, name: &OwnedName) {
+        self.xml.push_str("</");
+        push_qualified_name(&mut self.xml, name);
+        self.xml.push('>');
+        self.declared.pop();
     }
 }
 
-fn parse_attributes_top_level(graph: &mut MemoryGraph, attributes: &[OwnedAttribute])
-                              -> Result<(), PenyuError> {
+fn check_attributes_top_level(attributes: &[OwnedAttribute]) -> Result<(), PenyuError> {
     for attribute in attributes {
-        if attribute.name.local_name == "base" && has_ns(&attribute.name, vocabs::xml::NAMESPACE) {
-            let base = Iri::from(attribute.value.clone());
-            graph.set_base_ns(base);
-        } else {
+        if !has_ns(&attribute.name, vocabs::xml::NAMESPACE) {
             Err(PenyuError::from(format!("Unexpected attribute {:?}", attribute)))?
         }
     }
     Ok(())
 }
 
+fn push_qualified_name(xml: &mut String, name: &OwnedName) {
+    if let Some(prefix) = &name.prefix {
+        xml.push_str(prefix);
+        xml.push(':');
+    }
+    xml.push_str(&name.local_name);
+}
+
+fn is_whitespace(string: &str) -> bool {
+    string.chars().all(char::is_whitespace)
+}
+
+fn is_syntax_term(local_name: &str) -> bool {
+    matches!(local_name, "RDF" | "ID" | "about" | "parseType" | "resource" | "nodeID"
+        | "datatype" | "li" | "aboutEach" | "aboutEachPrefix" | "bagID")
+}
+
+fn is_property_attribute(attribute: &OwnedAttribute) -> Result<bool, PenyuError> {
+    match &attribute.name.namespace {
+        None => {
+            Err(PenyuError::from(format!("Unqualified attribute {:?}", attribute.name)))
+        }
+        Some(_) => {
+            let is_xml =
+                has_ns(&attribute.name, vocabs::xml::NAMESPACE)
+                    || attribute.name.prefix.as_ref().is_some_and(|prefix| {
+                    prefix.to_ascii_lowercase().starts_with("xml")
+                });
+            Ok(!is_xml)
+        }
+    }
+}
+
 fn tag_is_rdf_rdf(tag: &OwnedName) -> bool {
     tag.local_name == "RDF" && has_ns(tag, vocabs::rdf::NAMESPACE)
 }
@@ -328,8 +604,17 @@ fn has_ns(tag: &OwnedName, ns: &Iri) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use crate::error::PenyuError;
     use crate::model::graph::{Graph, MemoryGraph};
+    use crate::model::iri::Iri;
+    use crate::model::isomorphism::is_isomorphic;
+    use crate::model::node::{Entity, Node};
+    use crate::model::triple::Triple;
+    use crate::read::sink::TripleSink;
+    use crate::vocabs::rdf;
+    use std::collections::BTreeSet;
     use std::env::home_dir;
+    use std::fs::File;
     use std::path::PathBuf;
 
     fn ontologies_dir() -> PathBuf {
@@ -366,4 +651,121 @@ mod tests {
         assert_eq!(graph.prefixes().len(), 18);
         assert_eq!(graph.len(), 2305807);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn streams_to_sink() {
+        struct Counter {
+            n_triples: usize,
+            prefixes: Vec<String>,
+        }
+        impl TripleSink for Counter {
+            fn add_triple(&mut self, _triple: Triple) -> Result<(), PenyuError> {
+                self.n_triples += 1;
+                Ok(())
+            }
+            fn add_prefix(&mut self, prefix: String, _iri: Iri) -> Result<(), PenyuError> {
+                self.prefixes.push(prefix);
+                Ok(())
+            }
+        }
+        let xml = r#"<?xml version="1.0"?>
+            <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+                     xmlns:owl="http://www.w3.org/2002/07/owl#"
+                     xmlns:rdfs="http://www.w3.org/2000/01/rdf-schema#">
+                <owl:Class rdf:about="http://purl.obolibrary.org/obo/UBERON_0002107">
+                    <rdfs:label>liver</rdfs:label>
+                    <rdfs:label>jecur</rdfs:label>
+                </owl:Class>
+            </rdf:RDF>"#;
+        let mut counter = Counter { n_triples: 0, prefixes: Vec::new() };
+        super::read_to_sink(&mut xml.as_bytes(), &mut counter).unwrap();
+        assert_eq!(counter.n_triples, 3);
+        assert_eq!(counter.prefixes, vec!["", "owl", "rdf", "rdfs", "xml", "xmlns"]);
+    }
+
+    fn to_ntriples<G: Graph>(graph: &G) -> BTreeSet<String> {
+        let mut bytes: Vec<u8> = Vec::new();
+        crate::write::ntriples::write(&mut bytes, graph).unwrap();
+        String::from_utf8(bytes).unwrap().lines().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn validates_lang_tags() {
+        let xml = |lang: &str| {
+            format!(r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+                                xmlns:ex="http://example.org/" xml:lang="{}">
+                           <rdf:Description rdf:about="http://example.org/a" ex:p="x"/>
+                       </rdf:RDF>"#, lang)
+        };
+        let graph = super::read(&mut xml("EN-us").as_bytes()).unwrap();
+        assert!(to_ntriples(&graph).iter().all(|line| line.ends_with("\"x\"@en-US .")));
+        assert!(super::read(&mut xml("en_US").as_bytes()).is_err());
+    }
+
+    /// Where the W3C RDF/XML test suite is published, which its expected results use as base.
+    const SUITE_BASE: &str = "http://www.w3.org/2013/RDFXMLTests/";
+    const MF: &str = "http://www.w3.org/2001/sw/DataAccess/tests/test-manifest#";
+    const RDFT: &str = "http://www.w3.org/ns/rdftest#";
+
+    fn object(manifest: &MemoryGraph, subject: &Entity, predicate: String) -> Node {
+        let predicate = Iri::from(predicate);
+        let triple = manifest.triples_matching(Some(subject), Some(&predicate), None).next();
+        triple.unwrap_or_else(|| panic!("No {} in manifest", predicate)).object
+    }
+
+    fn object_iri(manifest: &MemoryGraph, subject: &Entity, predicate: String) -> String {
+        match object(manifest, subject, predicate) {
+            Node::Entity(Entity::Iri(iri)) => { iri.to_string() }
+            _ => { panic!("Expected an IRI in manifest") }
+        }
+    }
+
+    fn list_items(manifest: &MemoryGraph, mut list: Node) -> Vec<Entity> {
+        let mut items: Vec<Entity> = Vec::new();
+        while let Node::Entity(entity) = list {
+            if entity == Entity::Iri(rdf::NIL.clone()) {
+                break;
+            }
+            if let Node::Entity(item) = object(manifest, &entity, rdf::FIRST.to_string()) {
+                items.push(item);
+            }
+            list = object(manifest, &entity, rdf::REST.to_string());
+        }
+        items
+    }
+
+    /// Runs the tests in test-data/rdf-xml/manifest.ttl, which follows the manifest format of
+    /// the W3C RDF/XML test suite.
+    #[test]
+    fn passes_test_cases() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-data").join("rdf-xml");
+        let manifest =
+            crate::read::turtle::read(&mut File::open(dir.join("manifest.ttl")).unwrap()).unwrap();
+        let entries = Iri::from(format!("{}entries", MF));
+        let lists: Vec<Node> =
+            manifest.triples_matching(None, Some(&entries), None)
+                .map(|triple| triple.object).collect();
+        let mut n_positive: usize = 0;
+        let mut n_negative: usize = 0;
+        for test in lists.into_iter().flat_map(|list| list_items(&manifest, list)) {
+            let test_type = object_iri(&manifest, &test, rdf::TYPE.to_string());
+            let action = object_iri(&manifest, &test, format!("{}action", MF));
+            let base = Iri::from(format!("{}{}", SUITE_BASE, action));
+            let result = super::read_with_base(&mut File::open(dir.join(&action)).unwrap(), base);
+            if test_type == format!("{}TestXMLEval", RDFT) {
+                n_positive += 1;
+                let graph = result.unwrap_or_else(|error| panic!("{}: {}", action, error));
+                let expected = object_iri(&manifest, &test, format!("{}result", MF));
+                let expected =
+                    crate::read::ntriples::read(&mut File::open(dir.join(expected)).unwrap())
+                        .unwrap();
+                assert!(is_isomorphic(&graph, &expected), "{}: got\n{:#?}\nexpected\n{:#?}",
+                        action, to_ntriples(&graph), to_ntriples(&expected));
+            } else if test_type == format!("{}TestXMLNegativeSyntax", RDFT) {
+                n_negative += 1;
+                assert!(result.is_err(), "{} should fail to parse", action);
+            }
+        }
+        assert!(n_positive > 0 && n_negative > 0);
+    }
+}
@@ -1,6 +1,4 @@
-use strey::iter::Chars;
-
-pub fn is_valid_local(local: &Chars) -> bool {
+pub fn is_valid_local<I: Iterator<Item=char> + Clone>(local: &I) -> bool {
     let mut chars = local.clone();
     match chars.next() {
         Some('\\') => { if !is_escape_sequence(&mut chars) { return false; } }
@@ -8,6 +6,7 @@ pub fn is_valid_local(local: &Chars) -> bool {
         Some(c) if is_local_name_first_char(c) => {}
         _ => { return false; }
     }
+    let mut ends_with_dot = false;
     while let Some(c) = chars.next() {
         match c {
             '\\' => { if !is_escape_sequence(&mut chars) { return false; } }
@@ -15,11 +14,12 @@ pub fn is_valid_local(local: &Chars) -> bool {
             _ if is_local_name_later_char(c) => {}
             _ => { return false; }
         }
+        ends_with_dot = c == '.';
     }
-    true
+    !ends_with_dot
 }
 
-fn is_escape_sequence(chars: &mut Chars) -> bool {
+fn is_escape_sequence<I: Iterator<Item=char>>(chars: &mut I) -> bool {
     match chars.next() {
         Some('u') => is_hex_num(chars, 4),
         Some('U') => is_hex_num(chars, 8),
@@ -28,7 +28,7 @@ fn is_escape_sequence(chars: &mut Chars) -> bool {
     }
 }
 
-fn is_hex_num(chars: &mut Chars, len: usize) -> bool {
+fn is_hex_num<I: Iterator<Item=char>>(chars: &mut I, len: usize) -> bool {
     for _ in 0..len {
         match chars.next() {
             Some(c) if c.is_ascii_hexdigit() => {}
@@ -38,17 +38,40 @@ fn is_hex_num(chars: &mut Chars, len: usize) -> bool {
     true
 }
 
-fn is_single_char_escape(c: char) -> bool {
+pub fn is_single_char_escape(c: char) -> bool {
     c == 't' || c == 'b' || c == 'n' || c == 'r' || c == 'f' || c == '\'' || c == '"' || c == '\\'
 }
 
 fn is_local_name_first_char(c: char) -> bool {
-    is_local_name_later_char(c) || c == ':'
+    is_pn_chars_u(c) || c == ':' || c.is_ascii_digit()
 }
 
 fn is_local_name_later_char(c: char) -> bool {
-    c.is_alphanumeric() || c == '_' || c == '-' || c == '.'
+    is_pn_chars(c) || c == ':' || c == '.'
+}
+
+pub fn is_pn_chars_base(c: char) -> bool {
+    matches!(c, 'A'..='Z' | 'a'..='z' | '\u{00C0}'..='\u{00D6}' | '\u{00D8}'..='\u{00F6}'
+        | '\u{00F8}'..='\u{02FF}' | '\u{0370}'..='\u{037D}' | '\u{037F}'..='\u{1FFF}'
+        | '\u{200C}'..='\u{200D}' | '\u{2070}'..='\u{218F}' | '\u{2C00}'..='\u{2FEF}'
+        | '\u{3001}'..='\u{D7FF}' | '\u{F900}'..='\u{FDCF}' | '\u{FDF0}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{EFFFF}')
+}
+
+pub fn is_pn_chars_u(c: char) -> bool {
+    is_pn_chars_base(c) || c == '_'
+}
+
+pub fn is_pn_chars(c: char) -> bool {
+    is_pn_chars_u(c) || c == '-' || c.is_ascii_digit() || c == '\u{00B7}'
+        || ('\u{0300}'..='\u{036F}').contains(&c) || ('\u{203F}'..='\u{2040}').contains(&c)
+}
+
+pub fn is_pn_local_esc(c: char) -> bool {
+    matches!(c, '_' | '~' | '.' | '-' | '!' | '$' | '&' | '\'' | '(' | ')' | '*' | '+' | ','
+        | ';' | '=' | '/' | '?' | '#' | '@' | '%')
 }
+
 pub fn encode_local_name<I: Iterator<Item=char>>(chars: &mut I) -> String {
     let mut encoded = String::new();
     if let Some(first) = chars.next() {
@@ -88,4 +111,122 @@ pub fn encode_char(c: char) -> String {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+pub fn escape_string(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    for c in string.chars() {
+        match c {
+            '\x08' => escaped.push_str("\\b"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\x0C' => escaped.push_str("\\f"),
+            '\r' => escaped.push_str("\\r"),
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\x00'..='\x1F' | '\x7F' => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub fn escape_long_string(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    let mut chars = string.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' if chars.peek().is_none_or(|next| *next == '"') => escaped.push_str("\\\""),
+            '\t' | '\n' => escaped.push(c),
+            '\x08' => escaped.push_str("\\b"),
+            '\x0C' => escaped.push_str("\\f"),
+            '\r' => escaped.push_str("\\r"),
+            '\x00'..='\x1F' | '\x7F' => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Whether the IRI starts with a scheme, as opposed to being a relative reference.
+pub fn is_absolute_iri(iri: &str) -> bool {
+    match iri.split_once(':') {
+        Some((scheme, _)) => {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => { false }
+    }
+}
+
+pub fn is_valid_iri_char(c: char) -> bool {
+    !(c <= ' ' || matches!(c, '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\'))
+}
+
+/// Percent-encodes the characters that may not appear in an IRIREF, since escaping them as
+/// UCHARs would still stand for characters that readers must reject.
+pub fn escape_iri(iri: &str) -> String {
+    let mut escaped = String::with_capacity(iri.len());
+    for c in iri.chars() {
+        if is_valid_iri_char(c) {
+            escaped.push(c);
+        } else {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                escaped.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+    escaped
+}
+
+pub fn is_integer(string: &str) -> bool {
+    let digits = string.strip_prefix(['+', '-']).unwrap_or(string);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+pub fn is_decimal(string: &str) -> bool {
+    let unsigned = string.strip_prefix(['+', '-']).unwrap_or(string);
+    match unsigned.split_once('.') {
+        Some((integer, fraction)) => {
+            integer.chars().all(|c| c.is_ascii_digit())
+                && !fraction.is_empty() && fraction.chars().all(|c| c.is_ascii_digit())
+        }
+        None => { false }
+    }
+}
+
+pub fn is_double(string: &str) -> bool {
+    let unsigned = string.strip_prefix(['+', '-']).unwrap_or(string);
+    match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => {
+            let mantissa_digits = mantissa.chars().filter(|c| *c != '.').count();
+            mantissa_digits > 0 && mantissa.chars().filter(|c| *c == '.').count() <= 1
+                && mantissa.chars().all(|c| c.is_ascii_digit() || c == '.')
+                && is_integer(exponent)
+        }
+        None => { false }
+    }
+}
+
+/// Whether the string is a non-colonized XML name, as needed for prefixes and local names of
+/// XML QNames.
+pub fn is_nc_name(string: &str) -> bool {
+    let mut chars = string.chars();
+    chars.next().is_some_and(is_pn_chars_u) && chars.all(|c| is_pn_chars(c) || c == '.')
+}
+
+pub fn is_xml_char(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{10FFFF}')
+}
+
+pub fn escape_xml_text(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('\r', "&#xD;")
+}
+
+pub fn escape_xml_attribute(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('"', "&quot;")
+        .replace('\t', "&#x9;").replace('\n', "&#xA;").replace('\r', "&#xD;")
+}
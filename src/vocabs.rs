@@ -7,3 +7,4 @@ pub mod rdfs;
 pub mod obo_in_owl;
 pub mod hgnc;
 pub mod efo;
+pub mod owl;
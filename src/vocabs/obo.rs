@@ -2,6 +2,8 @@ use crate::model::iri::Iri;
 
 pub const PREFIX: &str = "obo";
 pub const NAMESPACE: &Iri = &Iri::new_str("http://purl.obolibrary.org/obo/");
+/// IAO:0000115, the "definition" annotation property.
+pub const DEFINITION: &Iri = &NAMESPACE.join_str("IAO_0000115");
 
 pub mod prefixes {
     pub const CHEBI: &str = "chebi";
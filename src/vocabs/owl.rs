@@ -0,0 +1,8 @@
+use crate::model::iri::Iri;
+
+pub const PREFIX: &str = "owl";
+pub const NAMESPACE: &Iri = &Iri::new_str("http://www.w3.org/2002/07/owl#");
+pub const ONTOLOGY: &Iri = &NAMESPACE.join_str("Ontology");
+pub const CLASS: &Iri = &NAMESPACE.join_str("Class");
+pub const THING: &Iri = &NAMESPACE.join_str("Thing");
+pub const DEPRECATED: &Iri = &NAMESPACE.join_str("deprecated");
@@ -13,6 +13,7 @@ pub const STATEMENT: &Iri = &NAMESPACE.join_str("Statement");
 pub const PROPERTY: &Iri = &NAMESPACE.join_str("Property");
 pub const XML_LITERAL: &Iri = &NAMESPACE.join_str("XMLLiteral");
 pub const LANG_STRING: &Iri = &NAMESPACE.join_str("langString");
+pub const JSON: &Iri = &NAMESPACE.join_str("JSON");
 pub const HTML: &Iri = &NAMESPACE.join_str("HTML");
 pub const PLAIN_LITERAL: &Iri = &NAMESPACE.join_str("PlainLiteral");
 pub const DATATYPE: &Iri = &NAMESPACE.join_str("Datatype");
@@ -1 +1,9 @@
-pub mod turtle;
\ No newline at end of file
+pub mod turtle;
+pub mod ntriples;
+pub mod nquads;
+pub mod trig;
+pub mod prefix_trie;
+pub mod xml;
+pub mod jsonld;
+pub mod canonical;
+mod layout;
\ No newline at end of file
@@ -0,0 +1,474 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use sha2::{Digest, Sha256};
+use crate::error::PenyuError;
+use crate::model::dataset::{Dataset, MemoryDataset};
+use crate::model::graph::{Graph, MemoryGraph};
+use crate::model::literal::{Literal, LiteralTag};
+use crate::model::node::{BlankNode, Entity, Node};
+use crate::model::quad::Quad;
+use crate::{syntax, vocabs};
+
+/// How many N-degree hash calls and permutations of related blank nodes canonicalization may
+/// go through before a dataset is rejected as too costly, which protects against datasets
+/// crafted to make canonicalization take exponential time.
+const MAX_N_DEGREE_STEPS: usize = 100_000;
+
+/// The canonical blank node labels (`c14n0`, `c14n1`, ...) for the dataset according to the
+/// RDF Dataset Canonicalization algorithm RDFC-1.0.
+pub fn canonical_labels<D: Dataset>(dataset: &D)
+                                    -> Result<BTreeMap<BlankNode, BlankNode>, PenyuError> {
+    let quads: Vec<Quad> = dataset.quads().collect();
+    labels(&quads)
+}
+
+/// The canonical blank node labels for the graph, as for a dataset with only a default graph.
+pub fn canonical_graph_labels<G: Graph>(graph: &G)
+                                        -> Result<BTreeMap<BlankNode, BlankNode>, PenyuError> {
+    labels(&graph_quads(graph))
+}
+
+pub fn canonicalize_dataset<D: Dataset>(dataset: &D) -> Result<MemoryDataset, PenyuError> {
+    let labels = canonical_labels(dataset)?;
+    let mut canonical = MemoryDataset::new();
+    if let Some(base_ns) = dataset.base_ns() {
+        canonical.set_base_ns(base_ns.clone());
+    }
+    for (prefix, iri) in dataset.prefixes() {
+        canonical.add_prefix(prefix.clone(), iri.clone());
+    }
+    for quad in dataset.quads() {
+        canonical.add_quad(quad.relabel(&labels));
+    }
+    Ok(canonical)
+}
+
+pub fn canonicalize_graph<G: Graph>(graph: &G) -> Result<MemoryGraph, PenyuError> {
+    let labels = canonical_graph_labels(graph)?;
+    let mut canonical = MemoryGraph::new();
+    if let Some(base_ns) = graph.base_ns() {
+        canonical.set_base_ns(base_ns.clone());
+    }
+    for (prefix, iri) in graph.prefixes() {
+        canonical.add_prefix(prefix.clone(), iri.clone());
+    }
+    for triple in graph.triples() {
+        canonical.add_triple(triple.relabel(&labels));
+    }
+    Ok(canonical)
+}
+
+/// Writes the dataset as canonical N-Quads: canonical blank node labels, canonical escapes
+/// and lines in code point order.
+pub fn write_nquads<W: Write, D: Dataset>(writer: &mut W, dataset: &D)
+                                          -> Result<(), PenyuError> {
+    let quads: Vec<Quad> = dataset.quads().collect();
+    writer.write_all(canonical_nquads(&quads)?.as_bytes())?;
+    Ok(())
+}
+
+/// Writes the graph as canonical N-Triples, as for `write_nquads`.
+pub fn write_ntriples<W: Write, G: Graph>(writer: &mut W, graph: &G) -> Result<(), PenyuError> {
+    writer.write_all(canonical_nquads(&graph_quads(graph))?.as_bytes())?;
+    Ok(())
+}
+
+/// The SHA-256 hash of the canonical N-Quads of the dataset, as lower-case hex digits.
+pub fn hash_dataset<D: Dataset>(dataset: &D) -> Result<String, PenyuError> {
+    let quads: Vec<Quad> = dataset.quads().collect();
+    Ok(sha256(&canonical_nquads(&quads)?))
+}
+
+/// The SHA-256 hash of the canonical N-Triples of the graph, as lower-case hex digits.
+pub fn hash_graph<G: Graph>(graph: &G) -> Result<String, PenyuError> {
+    Ok(sha256(&canonical_nquads(&graph_quads(graph))?))
+}
+
+fn graph_quads<G: Graph>(graph: &G) -> Vec<Quad> {
+    graph.triples().map(|triple| Quad::from_triple(triple, None)).collect()
+}
+
+fn canonical_nquads(quads: &[Quad]) -> Result<String, PenyuError> {
+    let labels = labels(quads)?;
+    let lines: BTreeSet<String> =
+        quads.iter().map(|quad| nquad(quad, &|blank_node| labels[blank_node].id())).collect();
+    Ok(lines.into_iter().collect())
+}
+
+fn sha256(string: &str) -> String {
+    Sha256::digest(string.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// A quad as a line of canonical N-Quads, labelling blank nodes as given.
+fn nquad<'a>(quad: &'a Quad, label: &dyn Fn(&'a BlankNode) -> &'a str) -> String {
+    let mut line = String::new();
+    push_entity(&mut line, &quad.subject, label);
+    line.push_str(&format!(" <{}> ", quad.predicate));
+    match &quad.object {
+        Node::Entity(entity) => { push_entity(&mut line, entity, label); }
+        Node::Literal(literal) => { push_literal(&mut line, literal); }
+    }
+    if let Some(graph_name) = &quad.graph_name {
+        line.push(' ');
+        push_entity(&mut line, graph_name, label);
+    }
+    line.push_str(" .\n");
+    line
+}
+
+fn push_entity<'a>(line: &mut String, entity: &'a Entity,
+                   label: &dyn Fn(&'a BlankNode) -> &'a str) {
+    match entity {
+        Entity::Iri(iri) => { line.push_str(&format!("<{}>", iri)); }
+        Entity::BlankNode(blank_node) => { line.push_str(&format!("_:{}", label(blank_node))); }
+    }
+}
+
+fn push_literal(line: &mut String, literal: &Literal) {
+    line.push_str(&format!("\"{}\"", syntax::escape_string(&literal.string)));
+    match &literal.literal_tag {
+        LiteralTag::Type(datatype) => {
+            if datatype != vocabs::xsd::STRING {
+                line.push_str(&format!("^^<{}>", datatype));
+            }
+        }
+        LiteralTag::LangTag(lang_tag) => {
+            line.push_str(&format!("@{}", lang_tag.to_ascii_lowercase()));
+        }
+    }
+}
+
+/// Issues identifiers with a prefix and a counter, remembering the order they were issued in.
+#[derive(Clone)]
+struct IdentifierIssuer {
+    prefix: &'static str,
+    issued: BTreeMap<BlankNode, String>,
+    order: Vec<BlankNode>,
+}
+
+impl IdentifierIssuer {
+    fn new(prefix: &'static str) -> IdentifierIssuer {
+        IdentifierIssuer { prefix, issued: BTreeMap::new(), order: Vec::new() }
+    }
+    fn get(&self, blank_node: &BlankNode) -> Option<&str> {
+        self.issued.get(blank_node).map(String::as_str)
+    }
+    fn issue(&mut self, blank_node: &BlankNode) -> String {
+        if let Some(identifier) = self.issued.get(blank_node) {
+            return identifier.clone();
+        }
+        let identifier = format!("{}{}", self.prefix, self.order.len());
+        self.issued.insert(blank_node.clone(), identifier.clone());
+        self.order.push(blank_node.clone());
+        identifier
+    }
+}
+
+struct Canonicalizer<'a> {
+    quads: &'a [Quad],
+    blank_quads: BTreeMap<BlankNode, Vec<&'a Quad>>,
+    first_degree_hashes: BTreeMap<BlankNode, String>,
+    canonical: IdentifierIssuer,
+    n_n_degree_steps: usize,
+}
+
+fn labels(quads: &[Quad]) -> Result<BTreeMap<BlankNode, BlankNode>, PenyuError> {
+    let mut canonicalizer = Canonicalizer {
+        quads,
+        blank_quads: BTreeMap::new(),
+        first_degree_hashes: BTreeMap::new(),
+        canonical: IdentifierIssuer::new("c14n"),
+        n_n_degree_steps: 0,
+    };
+    canonicalizer.issue_canonical_identifiers()?;
+    let labels =
+        canonicalizer.canonical.issued.into_iter().map(|(blank_node, identifier)| {
+            (blank_node, BlankNode::from(identifier))
+        }).collect();
+    Ok(labels)
+}
+
+fn blank_nodes(quad: &Quad) -> BTreeSet<&BlankNode> {
+    let object =
+        match &quad.object {
+            Node::Entity(entity) => { Some(entity) }
+            Node::Literal(_) => { None }
+        };
+    [Some(&quad.subject), object, quad.graph_name.as_ref()].into_iter().flatten()
+        .filter_map(|entity| match entity {
+            Entity::BlankNode(blank_node) => { Some(blank_node) }
+            Entity::Iri(_) => { None }
+        }).collect()
+}
+
+/// The permutations of the items, one at a time in lexicographic order of positions, since
+/// there may be far too many to hold in memory.
+struct Permutations<'a> {
+    items: &'a [BlankNode],
+    positions: Vec<usize>,
+    is_done: bool,
+}
+
+impl Iterator for Permutations<'_> {
+    type Item = Vec<BlankNode>;
+    fn next(&mut self) -> Option<Vec<BlankNode>> {
+        if self.is_done {
+            return None;
+        }
+        let permutation =
+            self.positions.iter().map(|position| self.items[*position].clone()).collect();
+        let positions = &mut self.positions;
+        match (1..positions.len()).rev().find(|i| positions[i - 1] < positions[*i]) {
+            Some(i) => {
+                let j = (i..positions.len()).rev().find(|j| positions[*j] > positions[i - 1]);
+                positions.swap(i - 1, j.unwrap());
+                positions[i..].reverse();
+            }
+            None => { self.is_done = true; }
+        }
+        Some(permutation)
+    }
+}
+
+fn permutations(items: &[BlankNode]) -> Permutations<'_> {
+    Permutations { items, positions: (0..items.len()).collect(), is_done: false }
+}
+
+impl<'a> Canonicalizer<'a> {
+    fn issue_canonical_identifiers(&mut self) -> Result<(), PenyuError> {
+        for quad in self.quads {
+            for blank_node in blank_nodes(quad) {
+                self.blank_quads.entry(blank_node.clone()).or_default().push(quad);
+            }
+        }
+        let mut hash_to_blank_nodes: BTreeMap<String, Vec<BlankNode>> = BTreeMap::new();
+        let blank_nodes: Vec<BlankNode> = self.blank_quads.keys().cloned().collect();
+        for blank_node in blank_nodes {
+            let hash = self.hash_first_degree_quads(&blank_node);
+            hash_to_blank_nodes.entry(hash).or_default().push(blank_node);
+        }
+        for blank_nodes in hash_to_blank_nodes.values() {
+            if let [blank_node] = blank_nodes.as_slice() {
+                self.canonical.issue(blank_node);
+            }
+        }
+        let shared = hash_to_blank_nodes.values().filter(|blank_nodes| blank_nodes.len() > 1);
+        for blank_nodes in shared {
+            let mut results: Vec<(String, IdentifierIssuer)> = Vec::new();
+            for blank_node in blank_nodes {
+                if self.canonical.get(blank_node).is_some() {
+                    continue;
+                }
+                let mut issuer = IdentifierIssuer::new("b");
+                issuer.issue(blank_node);
+                results.push(self.hash_n_degree_quads(blank_node, issuer)?);
+            }
+            results.sort_by(|(hash1, _), (hash2, _)| hash1.cmp(hash2));
+            for (_, issuer) in results {
+                for blank_node in &issuer.order {
+                    self.canonical.issue(blank_node);
+                }
+            }
+        }
+        Ok(())
+    }
+    fn hash_first_degree_quads(&mut self, blank_node: &BlankNode) -> String {
+        if let Some(hash) = self.first_degree_hashes.get(blank_node) {
+            return hash.clone();
+        }
+        let mut lines: Vec<String> =
+            self.blank_quads[blank_node].iter().map(|quad| {
+                nquad(quad, &|other| if other == blank_node { "a" } else { "z" })
+            }).collect();
+        lines.sort();
+        let hash = sha256(&lines.concat());
+        self.first_degree_hashes.insert(blank_node.clone(), hash.clone());
+        hash
+    }
+    fn hash_related_blank_node(&mut self, related: &BlankNode, quad: &Quad,
+                               issuer: &IdentifierIssuer, position: &str) -> String {
+        let identifier =
+            match self.canonical.get(related).or_else(|| issuer.get(related)) {
+                Some(identifier) => { format!("_:{}", identifier) }
+                None => { self.hash_first_degree_quads(related) }
+            };
+        let predicate =
+            if position == "g" { String::new() } else { format!("<{}>", quad.predicate) };
+        sha256(&format!("{}{}{}", position, predicate, identifier))
+    }
+    fn count_n_degree_step(&mut self) -> Result<(), PenyuError> {
+        self.n_n_degree_steps += 1;
+        if self.n_n_degree_steps > MAX_N_DEGREE_STEPS {
+            return Err(PenyuError::from(
+                "Dataset is too costly to canonicalize: too many N-degree hash steps"
+            ));
+        }
+        Ok(())
+    }
+    fn hash_n_degree_quads(&mut self, blank_node: &BlankNode, mut issuer: IdentifierIssuer)
+                           -> Result<(String, IdentifierIssuer), PenyuError> {
+        self.count_n_degree_step()?;
+        let mut related_by_hash: BTreeMap<String, Vec<BlankNode>> = BTreeMap::new();
+        let quads = self.blank_quads[blank_node].clone();
+        for quad in quads {
+            let components = [
+                (Some(&quad.subject), "s"),
+                (match &quad.object {
+                    Node::Entity(entity) => { Some(entity) }
+                    Node::Literal(_) => { None }
+                }, "o"),
+                (quad.graph_name.as_ref(), "g"),
+            ];
+            for (component, position) in components {
+                if let Some(Entity::BlankNode(related)) = component {
+                    if related != blank_node {
+                        let hash = self.hash_related_blank_node(related, quad, &issuer, position);
+                        related_by_hash.entry(hash).or_default().push(related.clone());
+                    }
+                }
+            }
+        }
+        let mut data_to_hash = String::new();
+        for (related_hash, related) in related_by_hash {
+            data_to_hash.push_str(&related_hash);
+            let mut chosen_path = String::new();
+            let mut chosen_issuer: Option<IdentifierIssuer> = None;
+            'permutations: for permutation in permutations(&related) {
+                self.count_n_degree_step()?;
+                let mut issuer_copy = issuer.clone();
+                let mut path = String::new();
+                let mut recursion_list: Vec<&BlankNode> = Vec::new();
+                let is_worse = |path: &str, chosen_path: &str| {
+                    !chosen_path.is_empty() && path.len() >= chosen_path.len()
+                        && path > chosen_path
+                };
+                for related in &permutation {
+                    match self.canonical.get(related) {
+                        Some(identifier) => { path.push_str(&format!("_:{}", identifier)); }
+                        None => {
+                            if issuer_copy.get(related).is_none() {
+                                recursion_list.push(related);
+                            }
+                            path.push_str(&format!("_:{}", issuer_copy.issue(related)));
+                        }
+                    }
+                    if is_worse(&path, &chosen_path) {
+                        continue 'permutations;
+                    }
+                }
+                for related in recursion_list {
+                    let (hash, result_issuer) =
+                        self.hash_n_degree_quads(related, issuer_copy.clone())?;
+                    path.push_str(&format!("_:{}", issuer_copy.issue(related)));
+                    path.push_str(&format!("<{}>", hash));
+                    issuer_copy = result_issuer;
+                    if is_worse(&path, &chosen_path) {
+                        continue 'permutations;
+                    }
+                }
+                if chosen_path.is_empty() || path < chosen_path {
+                    chosen_path = path;
+                    chosen_issuer = Some(issuer_copy);
+                }
+            }
+            data_to_hash.push_str(&chosen_path);
+            if let Some(chosen_issuer) = chosen_issuer {
+                issuer = chosen_issuer;
+            }
+        }
+        Ok((sha256(&data_to_hash), issuer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use crate::model::dataset::MemoryDataset;
+    use crate::model::node::BlankNode;
+    use crate::read::nquads;
+
+    fn canonical(input: &str) -> String {
+        let dataset = nquads::read(&mut input.as_bytes()).unwrap();
+        let mut bytes: Vec<u8> = Vec::new();
+        super::write_nquads(&mut bytes, &dataset).unwrap();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn labels_unique_hashes() {
+        // The example for unique hashes in the RDFC-1.0 specification.
+        let input = "\
+<http://example.com/#p> <http://example.com/#q> _:e0 .
+<http://example.com/#p> <http://example.com/#r> _:e1 .
+_:e0 <http://example.com/#s> <http://example.com/#u> .
+_:e1 <http://example.com/#t> <http://example.com/#u> .
+";
+        assert_eq!(canonical(input), "\
+<http://example.com/#p> <http://example.com/#q> _:c14n0 .
+<http://example.com/#p> <http://example.com/#r> _:c14n1 .
+_:c14n0 <http://example.com/#s> <http://example.com/#u> .
+_:c14n1 <http://example.com/#t> <http://example.com/#u> .
+");
+    }
+
+    #[test]
+    fn labels_shared_hashes() {
+        // The example for shared hashes in the RDFC-1.0 specification.
+        let input = "\
+<http://example.com/#p> <http://example.com/#q> _:e0 .
+<http://example.com/#p> <http://example.com/#q> _:e1 .
+_:e0 <http://example.com/#p> _:e2 .
+_:e1 <http://example.com/#p> _:e3 .
+_:e2 <http://example.com/#r> _:e3 .
+";
+        let expected = "\
+<http://example.com/#p> <http://example.com/#q> _:c14n2 .
+<http://example.com/#p> <http://example.com/#q> _:c14n3 .
+_:c14n0 <http://example.com/#r> _:c14n1 .
+_:c14n2 <http://example.com/#p> _:c14n1 .
+_:c14n3 <http://example.com/#p> _:c14n0 .
+";
+        assert_eq!(canonical(input), expected);
+        let relabeled = input.replace("_:e0", "_:x").replace("_:e3", "_:e0").replace("_:x", "_:e3");
+        assert_eq!(canonical(&relabeled), expected);
+    }
+
+    #[test]
+    fn hashes_independently_of_labels() {
+        let ring = |labels: [&str; 3]| {
+            let mut dataset = MemoryDataset::new();
+            for i in 0..3 {
+                let next = labels[(i + 1) % 3];
+                dataset.add(BlankNode::from(labels[i]), crate::vocabs::rdf::REST,
+                            BlankNode::from(next), Some(BlankNode::from("g").into()));
+            }
+            dataset
+        };
+        let hash = super::hash_dataset(&ring(["a", "b", "c"])).unwrap();
+        assert_eq!(hash, super::hash_dataset(&ring(["z", "y", "x"])).unwrap());
+        assert_eq!(hash.len(), 64);
+        let labels = super::canonical_labels(&ring(["a", "b", "c"])).unwrap();
+        assert_eq!(labels.len(), 4);
+        assert!(labels.values().all(|label| label.id().starts_with("c14n")));
+    }
+
+    #[test]
+    fn rejects_costly_datasets() {
+        let items: Vec<BlankNode> = ["a", "b", "c", "d"].map(BlankNode::from).to_vec();
+        let permutations: BTreeSet<Vec<BlankNode>> = super::permutations(&items).collect();
+        assert_eq!(permutations.len(), 24);
+        // Two alike nodes with twelve alike children each, which would take 12! permutations.
+        let mut input = String::new();
+        for parent in ["p", "q"] {
+            for i in 0..12 {
+                let quad = format!("_:{} <http://example.com/#c> _:{}{} .\n", parent, parent, i);
+                input.push_str(&quad);
+            }
+        }
+        let dataset = nquads::read(&mut input.as_bytes()).unwrap();
+        match super::canonical_labels(&dataset) {
+            Ok(_) => { panic!("Canonicalization should have been rejected as too costly") }
+            Err(error) => { assert!(error.to_string().contains("too costly"), "{}", error); }
+        }
+    }
+}
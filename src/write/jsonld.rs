@@ -0,0 +1,231 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use serde_json::{Map, Value};
+use crate::error::PenyuError;
+use crate::model::graph::Graph;
+use crate::model::iri::Iri;
+use crate::model::literal::LiteralTag;
+use crate::model::node::{Entity, Node};
+use crate::vocabs::{rdf, xsd};
+use crate::write::layout::Layout;
+use crate::write::prefix_trie::PrefixTrie;
+
+struct Context<'a> {
+    base: Option<&'a Iri>,
+    trie: PrefixTrie,
+}
+
+/// Writes the graph as compacted JSON-LD. The graph's prefixes become the `@context`, with
+/// its base as `@base`, and blank nodes referenced only once are nested.
+pub fn write<W: Write, G: Graph>(writer: &mut W, graph: &G) -> Result<(), PenyuError> {
+    let prefixes: BTreeMap<String, Iri> =
+        graph.prefixes().iter().filter(|(key, _)| is_usable_term(key))
+            .map(|(key, namespace)| (key.clone(), namespace.clone())).collect();
+    let context = Context { base: graph.base_ns().as_ref(), trie: PrefixTrie::new(&prefixes) };
+    let mut json_context = Map::new();
+    if let Some(base) = context.base {
+        json_context.insert("@base".to_string(), Value::String(base.to_string()));
+    }
+    for (key, namespace) in &prefixes {
+        let namespace = namespace.to_string();
+        // Only terms ending in a gen-delim character are prefixes without saying so.
+        let definition =
+            if namespace.ends_with([':', '/', '?', '#', '[', ']', '@']) {
+                Value::String(namespace)
+            } else {
+                let mut definition = Map::new();
+                definition.insert("@id".to_string(), Value::String(namespace));
+                definition.insert("@prefix".to_string(), Value::Bool(true));
+                Value::Object(definition)
+            };
+        json_context.insert(key.clone(), definition);
+    }
+    let subjects: BTreeSet<Entity> = graph.triples().map(|triple| triple.subject).collect();
+    let layout = Layout::new(graph, &subjects);
+    let nodes: Vec<Value> =
+        subjects.iter().filter(|subject| !layout.is_inlined(subject))
+            .map(|subject| node_object(graph, &layout, subject, &context)).collect();
+    let mut document = Map::new();
+    document.insert("@context".to_string(), Value::Object(json_context));
+    document.insert("@graph".to_string(), Value::Array(nodes));
+    serde_json::to_writer_pretty(&mut *writer, &Value::Object(document))?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+fn is_usable_term(key: &str) -> bool {
+    !key.is_empty() && key != "_" && !key.contains(':') && !key.starts_with('@')
+}
+
+/// A compact IRI if a prefix matches, else the full IRI.
+fn compact_iri(iri: &Iri, context: &Context) -> String {
+    let iri = iri.to_string();
+    let compacted =
+        context.trie.longest_match(&iri, |local| !local.starts_with("//"))
+            .map(|(key, local)| format!("{}:{}", key, local));
+    compacted.unwrap_or(iri)
+}
+
+/// Like `compact_iri`, but may also use an IRI relative to the base, as `@id` values can.
+fn compact_id(entity: &Entity, context: &Context) -> String {
+    match entity {
+        Entity::Iri(iri) => {
+            let compacted = compact_iri(iri, context);
+            if compacted != iri.to_string() {
+                return compacted;
+            }
+            // A relative IRI with a colon in its first segment would be taken as absolute.
+            let relative =
+                context.base.and_then(|base| base.relativize(iri)).filter(|relative| {
+                    !relative.split('/').next().unwrap_or_default().contains(':')
+                });
+            relative.unwrap_or(compacted)
+        }
+        Entity::BlankNode(blank_node) => { format!("_:{}", blank_node.id()) }
+    }
+}
+
+fn single_or_array(mut values: Vec<Value>) -> Value {
+    if values.len() == 1 { values.remove(0) } else { Value::Array(values) }
+}
+
+fn node_object<G: Graph>(graph: &G, layout: &Layout, subject: &Entity, context: &Context)
+                         -> Value {
+    let mut object = Map::new();
+    if !layout.is_inlined(subject) {
+        object.insert("@id".to_string(), Value::String(compact_id(subject, context)));
+    }
+    let mut types: Vec<Value> = Vec::new();
+    let mut properties: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+    for triple in graph.triples_matching(Some(subject), None, None) {
+        match &triple.object {
+            Node::Entity(Entity::Iri(type_iri)) if triple.predicate == *rdf::TYPE => {
+                types.push(Value::String(compact_iri(type_iri, context)));
+            }
+            _ => {
+                let value = value_object(graph, layout, &triple.object, context);
+                properties.entry(compact_iri(&triple.predicate, context)).or_default().push(value);
+            }
+        }
+    }
+    if !types.is_empty() {
+        object.insert("@type".to_string(), single_or_array(types));
+    }
+    for (key, values) in properties {
+        object.insert(key, single_or_array(values));
+    }
+    Value::Object(object)
+}
+
+fn value_object<G: Graph>(graph: &G, layout: &Layout, node: &Node, context: &Context) -> Value {
+    let mut object = Map::new();
+    match node {
+        Node::Literal(literal) => {
+            match &literal.literal_tag {
+                LiteralTag::Type(datatype) if datatype == xsd::STRING => {
+                    return Value::String(literal.string.clone());
+                }
+                LiteralTag::Type(datatype) => {
+                    object.insert("@value".to_string(), Value::String(literal.string.clone()));
+                    object.insert("@type".to_string(),
+                                  Value::String(compact_iri(datatype, context)));
+                }
+                LiteralTag::LangTag(lang_tag) => {
+                    object.insert("@value".to_string(), Value::String(literal.string.clone()));
+                    object.insert("@language".to_string(), Value::String(lang_tag.clone()));
+                }
+            }
+        }
+        Node::Entity(entity) if layout.is_inlined(entity) => {
+            match layout.list_items(graph, entity) {
+                Some(items) => {
+                    let items: Vec<Value> =
+                        items.iter().map(|item| value_object(graph, layout, item, context))
+                            .collect();
+                    object.insert("@list".to_string(), Value::Array(items));
+                }
+                None => { return node_object(graph, layout, entity, context); }
+            }
+        }
+        Node::Entity(entity) => {
+            object.insert("@id".to_string(), Value::String(compact_id(entity, context)));
+        }
+    }
+    Value::Object(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::graph::{Graph, MemoryGraph};
+    use crate::model::iri::Iri;
+    use crate::model::literal::{Literal, LiteralTag};
+    use crate::model::node::BlankNode;
+    use crate::vocabs::{obo, rdf, rdfs, xsd};
+
+    fn n_triples(graph: &MemoryGraph) -> String {
+        let mut bytes: Vec<u8> = Vec::new();
+        crate::write::ntriples::write(&mut bytes, graph).unwrap();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut graph = MemoryGraph::new();
+        graph.set_base_ns(Iri::from("http://example.org/data/"));
+        graph.add_prefix(obo::PREFIX.to_string(), obo::NAMESPACE.clone());
+        graph.add_prefix(obo::prefixes::UBERON.to_string(), obo::ns::UBERON.clone());
+        graph.add_prefix(rdfs::PREFIX.to_string(), rdfs::NAMESPACE.clone());
+        graph.add_prefix(xsd::PREFIX.to_string(), xsd::NAMESPACE.clone());
+        let liver = obo::ns::UBERON.append("0002107".to_string());
+        graph.add(&liver, rdf::TYPE, rdfs::CLASS);
+        graph.add(&liver, rdfs::LABEL,
+                  Literal::new("liver".to_string(), LiteralTag::LangTag("en".to_string())));
+        graph.add(&liver, rdfs::LABEL, Literal::from("Liver \"organ\"".to_string()));
+        graph.add(&liver, Iri::from("http://example.org/data/weight"),
+                  Literal::new("1.5".to_string(), LiteralTag::Type(xsd::DECIMAL.clone())));
+        graph.add(&liver, rdfs::SEE_ALSO, Iri::from("http://example.org/data/body"));
+        graph.add(Iri::from("http://example.org/data/body"), rdfs::SEE_ALSO,
+                  Iri::from("urn:x:body"));
+        let mut bytes: Vec<u8> = Vec::new();
+        super::write(&mut bytes, &graph).unwrap();
+        let json = String::from_utf8(bytes).unwrap();
+        assert!(json.contains("\"@id\": \"uberon:0002107\""), "{}", json);
+        assert!(json.contains("\"uberon\": {"), "{}", json);
+        assert!(json.contains("\"@id\": \"body\""), "{}", json);
+        let read = crate::read::jsonld::read(&mut json.as_bytes()).unwrap();
+        assert_eq!(n_triples(&read), n_triples(&graph));
+        assert!(read.base_ns().as_ref() == graph.base_ns().as_ref());
+        assert_eq!(read.prefixes().len(), 4);
+    }
+
+    #[test]
+    fn nests_blank_nodes_and_lists() {
+        let mut graph = MemoryGraph::new();
+        graph.add_prefix("ex".to_string(), Iri::from("http://example.org/"));
+        let ex = |local: &str| Iri::from(format!("http://example.org/{}", local));
+        graph.add(ex("a"), ex("p"), BlankNode::from("b0"));
+        graph.add(BlankNode::from("b0"), ex("q"), Literal::from("x".to_string()));
+        graph.add(ex("a"), ex("list"), BlankNode::from("l0"));
+        graph.add(BlankNode::from("l0"), rdf::FIRST, ex("b"));
+        graph.add(BlankNode::from("l0"), rdf::REST, BlankNode::from("l1"));
+        graph.add(BlankNode::from("l1"), rdf::FIRST, Literal::from(true));
+        graph.add(BlankNode::from("l1"), rdf::REST, rdf::NIL);
+        let mut bytes: Vec<u8> = Vec::new();
+        super::write(&mut bytes, &graph).unwrap();
+        let expected = serde_json::json!({
+            "@context": { "ex": "http://example.org/" },
+            "@graph": [{
+                "@id": "ex:a",
+                "ex:list": { "@list": [
+                    { "@id": "ex:b" },
+                    { "@value": "true", "@type": "http://www.w3.org/2001/XMLSchema#boolean" }
+                ] },
+                "ex:p": { "ex:q": "x" }
+            }]
+        });
+        let written: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(written, expected);
+        let read = crate::read::jsonld::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(read.len(), graph.len());
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::{BTreeMap, BTreeSet};
+use crate::model::graph::Graph;
+use crate::model::node::{BlankNode, Entity, Node};
+use crate::vocabs;
+
+/// Decides which blank nodes are written nested inside the one statement that refers to them,
+/// rather than at the top level with a label.
+pub(crate) struct Layout {
+    n_references: BTreeMap<BlankNode, usize>,
+    inlined: BTreeSet<BlankNode>,
+}
+
+impl Layout {
+    pub(crate) fn new<G: Graph>(graph: &G, subjects: &BTreeSet<Entity>) -> Layout {
+        Layout::with_labeled(graph, subjects, &BTreeSet::new())
+    }
+    /// Like `new`, but never inlines the given blank nodes, such as those that are also
+    /// referenced from outside the graph.
+    pub(crate) fn with_labeled<G: Graph>(graph: &G, subjects: &BTreeSet<Entity>,
+                                         labeled: &BTreeSet<BlankNode>) -> Layout {
+        let mut n_references: BTreeMap<BlankNode, usize> = BTreeMap::new();
+        for triple in graph.triples() {
+            if let Node::Entity(Entity::BlankNode(blank_node)) = triple.object {
+                *n_references.entry(blank_node).or_default() += 1;
+            }
+        }
+        // Counting them twice keeps them from being inlined.
+        for blank_node in labeled {
+            *n_references.entry(blank_node.clone()).or_default() += 2;
+        }
+        let mut layout = Layout { n_references, inlined: BTreeSet::new() };
+        let mut placed: BTreeSet<Entity> = BTreeSet::new();
+        for subject in subjects {
+            if !layout.is_inlinable(subject) {
+                layout.place(graph, subject, &mut placed);
+            }
+        }
+        // Whatever is left is only reachable through a cycle of blank nodes, so one of them
+        // needs to be written at the top level.
+        for subject in subjects {
+            if !placed.contains(subject) {
+                layout.place(graph, subject, &mut placed);
+            }
+        }
+        layout
+    }
+    fn is_inlinable(&self, entity: &Entity) -> bool {
+        match entity {
+            Entity::BlankNode(blank_node) => { self.n_references.get(blank_node) == Some(&1) }
+            Entity::Iri(_) => { false }
+        }
+    }
+    fn place<G: Graph>(&mut self, graph: &G, root: &Entity, placed: &mut BTreeSet<Entity>) {
+        placed.insert(root.clone());
+        let mut stack: Vec<Entity> = vec![root.clone()];
+        while let Some(entity) = stack.pop() {
+            for triple in graph.triples_matching(Some(&entity), None, None) {
+                if let Node::Entity(object) = triple.object {
+                    if self.is_inlinable(&object) && placed.insert(object.clone()) {
+                        if let Entity::BlankNode(blank_node) = &object {
+                            self.inlined.insert(blank_node.clone());
+                        }
+                        stack.push(object);
+                    }
+                }
+            }
+        }
+    }
+    pub(crate) fn is_inlined(&self, entity: &Entity) -> bool {
+        match entity {
+            Entity::BlankNode(blank_node) => { self.inlined.contains(blank_node) }
+            Entity::Iri(_) => { false }
+        }
+    }
+    pub(crate) fn list_items<G: Graph>(&self, graph: &G, head: &Entity) -> Option<Vec<Node>> {
+        let mut items: Vec<Node> = Vec::new();
+        let mut cell = head.clone();
+        loop {
+            let mut first: Option<Node> = None;
+            let mut rest: Option<Node> = None;
+            for triple in graph.triples_matching(Some(&cell), None, None) {
+                if triple.predicate == *vocabs::rdf::FIRST && first.is_none() {
+                    first = Some(triple.object);
+                } else if triple.predicate == *vocabs::rdf::REST && rest.is_none() {
+                    rest = Some(triple.object);
+                } else {
+                    return None;
+                }
+            }
+            items.push(first?);
+            match rest? {
+                Node::Entity(Entity::Iri(iri)) if iri == *vocabs::rdf::NIL => {
+                    return Some(items);
+                }
+                Node::Entity(next) if self.is_inlined(&next) && next != *head => {
+                    cell = next;
+                }
+                _ => { return None; }
+            }
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use std::io::Write;
+use crate::error::PenyuError;
+use crate::model::dataset::Dataset;
+use crate::model::quad::Quad;
+use crate::write::ntriples::{write_entity, write_iri, write_node};
+
+pub fn write<W: Write, D: Dataset>(writer: &mut W, dataset: &D) -> Result<(), PenyuError> {
+    for quad in dataset.quads() {
+        write_quad(writer, &quad)?;
+    }
+    Ok(())
+}
+
+pub fn write_quad<W: Write>(writer: &mut W, quad: &Quad) -> Result<(), PenyuError> {
+    write_entity(writer, &quad.subject)?;
+    write!(writer, " ")?;
+    write_iri(writer, &quad.predicate)?;
+    write!(writer, " ")?;
+    write_node(writer, &quad.object)?;
+    if let Some(graph_name) = &quad.graph_name {
+        write!(writer, " ")?;
+        write_entity(writer, graph_name)?;
+    }
+    writeln!(writer, " .")?;
+    Ok(())
+}
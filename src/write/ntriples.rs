@@ -0,0 +1,98 @@
+use std::io::Write;
+use crate::error::PenyuError;
+use crate::model::graph::Graph;
+use crate::model::iri::Iri;
+use crate::model::literal::{Literal, LiteralTag};
+use crate::model::node::{BlankNode, Entity, Node};
+use crate::model::triple::Triple;
+use crate::{syntax, vocabs};
+
+pub fn write<W: Write, G: Graph>(writer: &mut W, graph: &G) -> Result<(), PenyuError> {
+    for triple in graph.triples() {
+        write_triple(writer, &triple)?;
+    }
+    Ok(())
+}
+
+pub fn write_triple<W: Write>(writer: &mut W, triple: &Triple) -> Result<(), PenyuError> {
+    write_entity(writer, &triple.subject)?;
+    write!(writer, " ")?;
+    write_iri(writer, &triple.predicate)?;
+    write!(writer, " ")?;
+    write_node(writer, &triple.object)?;
+    writeln!(writer, " .")?;
+    Ok(())
+}
+
+pub(crate) fn write_node<W: Write>(writer: &mut W, node: &Node) -> Result<(), PenyuError> {
+    match node {
+        Node::Entity(entity) => { write_entity(writer, entity) }
+        Node::Literal(literal) => { write_literal(writer, literal) }
+    }
+}
+
+pub(crate) fn write_entity<W: Write>(writer: &mut W, entity: &Entity) -> Result<(), PenyuError> {
+    match entity {
+        Entity::Iri(iri) => { write_iri(writer, iri) }
+        Entity::BlankNode(blank_node) => { write_blank_node(writer, blank_node) }
+    }
+}
+
+pub(crate) fn write_iri<W: Write>(writer: &mut W, iri: &Iri) -> Result<(), PenyuError> {
+    write!(writer, "<{}>", syntax::escape_iri(&iri.to_string()))?;
+    Ok(())
+}
+
+fn write_blank_node<W: Write>(writer: &mut W, blank_node: &BlankNode) -> Result<(), PenyuError> {
+    write!(writer, "_:{}", blank_node.id())?;
+    Ok(())
+}
+
+fn write_literal<W: Write>(writer: &mut W, literal: &Literal) -> Result<(), PenyuError> {
+    write!(writer, "\"{}\"", syntax::escape_string(&literal.string))?;
+    match &literal.literal_tag {
+        LiteralTag::Type(type_iri) => {
+            if type_iri != vocabs::xsd::STRING {
+                write!(writer, "^^")?;
+                write_iri(writer, type_iri)?;
+            }
+        }
+        LiteralTag::LangTag(lang_tag) => { write!(writer, "@{}", lang_tag)? }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::graph::MemoryGraph;
+    use crate::model::iri::Iri;
+    use crate::model::literal::{Literal, LiteralTag};
+    use crate::model::node::BlankNode;
+    use crate::vocabs::{obo, rdfs, xsd};
+
+    #[test]
+    fn writes_lines() {
+        let mut graph = MemoryGraph::new();
+        graph.add_prefix(obo::PREFIX.to_string(), obo::NAMESPACE.clone());
+        let liver = obo::ns::UBERON.append("0002107".to_string());
+        graph.add(&liver, rdfs::LABEL, Literal::from("the \"liver\"\n".to_string()));
+        graph.add(&liver, rdfs::LABEL,
+                  Literal::new("Leber".to_string(), LiteralTag::LangTag("de".to_string())));
+        graph.add(&liver, Iri::from("http://example.org/weight"),
+                  Literal::new("1.5".to_string(), LiteralTag::Type(xsd::DECIMAL.clone())));
+        graph.add(BlankNode::from("b0"), rdfs::SEE_ALSO, Iri::from("http://example.org/a b"));
+        let mut bytes: Vec<u8> = Vec::new();
+        super::write(&mut bytes, &graph).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(),
+                   "<http://purl.obolibrary.org/obo/UBERON_0002107> \
+                    <http://example.org/weight> \
+                    \"1.5\"^^<http://www.w3.org/2001/XMLSchema#decimal> .\n\
+                    <http://purl.obolibrary.org/obo/UBERON_0002107> \
+                    <http://www.w3.org/2000/01/rdf-schema#label> \"Leber\"@de .\n\
+                    <http://purl.obolibrary.org/obo/UBERON_0002107> \
+                    <http://www.w3.org/2000/01/rdf-schema#label> \
+                    \"the \\\"liver\\\"\\n\" .\n\
+                    _:b0 <http://www.w3.org/2000/01/rdf-schema#seeAlso> \
+                    <http://example.org/a%20b> .\n");
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+use crate::model::iri::Iri;
+use crate::syntax;
+
+/// Maps IRIs to the longest registered namespace they start with, so that compacting an IRI
+/// takes time proportional to the length of the IRI rather than the number of prefixes.
+pub struct PrefixTrie {
+    nodes: Vec<TrieNode>,
+}
+
+#[derive(Default)]
+struct TrieNode {
+    children: BTreeMap<char, usize>,
+    key: Option<String>,
+}
+
+impl PrefixTrie {
+    pub fn new(prefixes: &BTreeMap<String, Iri>) -> PrefixTrie {
+        let mut trie = PrefixTrie { nodes: vec![TrieNode::default()] };
+        for (key, namespace) in prefixes {
+            trie.insert(key, &namespace.to_string());
+        }
+        trie
+    }
+    fn insert(&mut self, key: &str, namespace: &str) {
+        let mut i_node: usize = 0;
+        for c in namespace.chars() {
+            i_node =
+                match self.nodes[i_node].children.get(&c) {
+                    Some(i_child) => { *i_child }
+                    None => {
+                        let i_child = self.nodes.len();
+                        self.nodes.push(TrieNode::default());
+                        self.nodes[i_node].children.insert(c, i_child);
+                        i_child
+                    }
+                };
+        }
+        // If two prefixes share a namespace, the first in order wins.
+        if self.nodes[i_node].key.is_none() {
+            self.nodes[i_node].key = Some(key.to_string());
+        }
+    }
+    /// The prefix with the longest namespace that starts the IRI and leaves a local part the
+    /// predicate accepts, with that local part.
+    pub fn longest_match<'a, 'b, F>(&'a self, iri: &'b str, is_valid_local: F)
+                                    -> Option<(&'a str, &'b str)>
+    where
+        F: Fn(&str) -> bool,
+    {
+        let mut longest: Option<(&str, &str)> = None;
+        let mut i_node: usize = 0;
+        let mut char_indices = iri.char_indices();
+        loop {
+            let node = &self.nodes[i_node];
+            let (pos, c) =
+                match char_indices.next() {
+                    Some((pos, c)) => { (pos, Some(c)) }
+                    None => { (iri.len(), None) }
+                };
+            if let Some(key) = &node.key {
+                let local = &iri[pos..];
+                if is_valid_local(local) {
+                    longest = Some((key.as_str(), local));
+                }
+            }
+            match c.and_then(|c| node.children.get(&c)) {
+                Some(i_child) => { i_node = *i_child; }
+                None => { break; }
+            }
+        }
+        longest
+    }
+    /// The prefix with the longest namespace that leaves a valid local name, if any.
+    pub fn compact<'a, 'b>(&'a self, iri: &'b str) -> Option<(&'a str, &'b str)> {
+        self.longest_match(iri, |local| syntax::is_valid_local(&local.chars()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use crate::vocabs::obo;
+    use crate::write::prefix_trie::PrefixTrie;
+
+    #[test]
+    fn prefers_longest_namespace() {
+        let mut prefixes = BTreeMap::new();
+        prefixes.insert(obo::PREFIX.to_string(), obo::NAMESPACE.clone());
+        prefixes.insert(obo::prefixes::MONDO.to_string(), obo::ns::MONDO.clone());
+        prefixes.insert("mondo2".to_string(), obo::ns::MONDO.clone());
+        prefixes.insert("ex".to_string(), "http://example.org/".into());
+        let trie = PrefixTrie::new(&prefixes);
+        assert_eq!(trie.compact("http://purl.obolibrary.org/obo/MONDO_0005148"),
+                   Some(("mondo", "0005148")));
+        assert_eq!(trie.compact("http://purl.obolibrary.org/obo/UBERON_0002107"),
+                   Some(("obo", "UBERON_0002107")));
+        assert_eq!(trie.compact("http://purl.obolibrary.org/obo/MONDO_"),
+                   Some(("obo", "MONDO_")));
+        assert_eq!(trie.compact("http://purl.obolibrary.org/obo/MONDO_/x"), None);
+        assert_eq!(trie.compact("http://purl.obolibrary.org/ob"), None);
+        assert_eq!(trie.compact("http://example.org/a.b"), Some(("ex", "a.b")));
+        assert_eq!(trie.longest_match("http://purl.obolibrary.org/obo/MONDO_1", |local| {
+            local.starts_with('M')
+        }), Some(("obo", "MONDO_1")));
+    }
+}
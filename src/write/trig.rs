@@ -0,0 +1,81 @@
+use std::io::Write;
+use crate::error::PenyuError;
+use crate::model::dataset::Dataset;
+use crate::model::graph::{MemoryGraph, MutableGraph};
+use crate::model::node::Entity;
+use crate::write::turtle::{self, TurtleWriterConfig};
+
+pub fn write<W: Write, D: Dataset>(writer: &mut W, dataset: &D) -> Result<(), PenyuError> {
+    write_with_config(writer, dataset, &TurtleWriterConfig::default())
+}
+
+pub fn write_with_config<W: Write, D: Dataset>(writer: &mut W, dataset: &D,
+                                               config: &TurtleWriterConfig)
+                                               -> Result<(), PenyuError> {
+    let graph_names: Vec<Option<Entity>> =
+        std::iter::once(None).chain(dataset.graph_names().cloned().map(Some)).collect();
+    let graphs: Vec<(Option<Entity>, MemoryGraph)> =
+        graph_names.into_iter().map(|graph_name| {
+            let mut graph = MemoryGraph::new();
+            graph.extend(dataset.quads_matching(None, None, None, Some(graph_name.as_ref()))
+                .map(|quad| quad.into_triple()));
+            (graph_name, graph)
+        }).collect();
+    turtle::write_graphs(writer, dataset.base_ns().as_ref(), dataset.prefixes(), &graphs, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::dataset::{Dataset, MemoryDataset};
+    use crate::model::iri::Iri;
+    use crate::model::literal::Literal;
+    use crate::model::node::{BlankNode, Entity, Node};
+    use crate::vocabs::{obo, rdfs};
+
+    fn round_trip(dataset: &MemoryDataset) -> MemoryDataset {
+        let mut bytes: Vec<u8> = Vec::new();
+        super::write(&mut bytes, dataset).unwrap();
+        crate::read::trig::read(&mut bytes.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn writes_graph_blocks() {
+        let mut dataset = MemoryDataset::new();
+        dataset.add_prefix(obo::PREFIX.to_string(), obo::NAMESPACE.clone());
+        dataset.add_prefix(rdfs::PREFIX.to_string(), rdfs::NAMESPACE.clone());
+        let liver = obo::Ontology::UBERON.create_iri(2107);
+        let uberon = Entity::from(Iri::from("http://purl.obolibrary.org/obo/uberon.owl"));
+        dataset.add(&liver, rdfs::LABEL, Literal::from("liver".to_string()), None);
+        dataset.add(&liver, rdfs::LABEL, Literal::from("jecur".to_string()), Some(uberon));
+        let mut bytes: Vec<u8> = Vec::new();
+        super::write(&mut bytes, &dataset).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(),
+                   "PREFIX obo: <http://purl.obolibrary.org/obo/>\n\
+                    PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>\n\
+                    obo:UBERON_0002107 rdfs:label \"liver\" .\n\
+                    obo:uberon.owl {\n    \
+                    obo:UBERON_0002107 rdfs:label \"jecur\" .\n\
+                    }\n");
+    }
+
+    #[test]
+    fn keeps_blank_nodes_shared_between_graphs() {
+        let ex = |local: &str| Iri::from(format!("http://example.org/{}", local));
+        let mut dataset = MemoryDataset::new();
+        dataset.add(ex("a"), ex("p"), BlankNode::from("g"), None);
+        dataset.add(ex("b"), ex("q"), ex("c"), Some(BlankNode::from("g").into()));
+        let read = round_trip(&dataset);
+        let object = read.quads_matching(None, Some(&ex("p")), None, None).next().unwrap().object;
+        let graph_name = read.graph_names().next().unwrap().clone();
+        assert!(object == Node::from(graph_name));
+        let mut dataset = MemoryDataset::new();
+        dataset.add(ex("a"), ex("p"), BlankNode::from("x"), Some(Entity::from(ex("g2"))));
+        dataset.add(ex("b"), ex("p"), BlankNode::from("x"), Some(Entity::from(ex("g3"))));
+        let read = round_trip(&dataset);
+        let objects: Vec<Node> =
+            read.quads_matching(None, Some(&ex("p")), None, None).map(|quad| quad.object)
+                .collect();
+        assert_eq!(objects.len(), 2);
+        assert!(objects[0] == objects[1]);
+    }
+}
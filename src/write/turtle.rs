@@ -1,96 +1,401 @@
-use std::collections::BTreeMap;
+use std::cell::{RefCell, RefMut};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
 use std::io::Write;
 use crate::error::PenyuError;
-use crate::model::graph::Graph;
+use crate::model::graph::{Graph, MemoryGraph};
 use crate::model::node::{BlankNode, Entity, Node};
 use crate::model::iri::Iri;
 use crate::model::literal::{Literal, LiteralTag};
+use crate::model::triple::Triple;
+use crate::write::layout::Layout;
+use crate::write::prefix_trie::PrefixTrie;
 use crate::{syntax, vocabs};
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PrefixSyntax {
+    /// `PREFIX obo: <...>` and `BASE <...>`
+    Sparql,
+    /// `@prefix obo: <...> .` and `@base <...> .`
+    Turtle,
+}
+
+#[derive(Clone)]
+pub struct TurtleWriterConfig {
+    pub prefix_syntax: PrefixSyntax,
+    /// Write `a` instead of `rdf:type` in predicate position.
+    pub use_a_for_type: bool,
+    /// Declare only the prefixes that are used to abbreviate at least one IRI.
+    pub only_used_prefixes: bool,
+    /// Write integers, decimals, doubles and booleans without quotes and datatype where the
+    /// lexical form allows it.
+    pub abbreviate_literals: bool,
+    /// Number of spaces per level of indentation.
+    pub indent: usize,
+    /// Write IRIs relative to the base, if there is one.
+    pub relative_iris: bool,
+}
+
+impl Default for TurtleWriterConfig {
+    fn default() -> Self {
+        TurtleWriterConfig {
+            prefix_syntax: PrefixSyntax::Sparql,
+            use_a_for_type: false,
+            only_used_prefixes: false,
+            abbreviate_literals: false,
+            indent: 4,
+            relative_iris: true,
+        }
+    }
+}
+
+struct Context<'a> {
+    prefixes: &'a BTreeMap<String, Iri>,
+    trie: &'a PrefixTrie,
+    base: Option<&'a Iri>,
+    config: &'a TurtleWriterConfig,
+    /// Reused for each IRI to compact, rather than allocating a string per IRI.
+    buffer: &'a RefCell<String>,
+}
+
+impl<'a> Context<'a> {
+    fn indent(&self, depth: usize) -> String {
+        " ".repeat(self.config.indent * depth)
+    }
+    fn render(&self, iri: &Iri) -> RefMut<'_, String> {
+        let mut buffer = self.buffer.borrow_mut();
+        buffer.clear();
+        write!(buffer, "{}", iri).expect("Writing to a string cannot fail");
+        buffer
+    }
+    /// The prefix the IRI is written with, if any.
+    fn prefix_key(&self, iri: &Iri) -> Option<&'a str> {
+        self.trie.compact(&self.render(iri)).map(|(key, _)| key)
+    }
+}
+
 pub fn write<W: Write, G: Graph>(writer: &mut W, graph: &G) -> Result<(), PenyuError> {
-    write_default_ns(writer, graph)?;
-    write_prefixes(writer, graph)?;
-    write_triples(writer, graph)?;
+    write_with_config(writer, graph, &TurtleWriterConfig::default())
+}
+
+pub fn write_with_config<W: Write, G: Graph>(writer: &mut W, graph: &G,
+                                             config: &TurtleWriterConfig)
+                                             -> Result<(), PenyuError> {
+    let trie = PrefixTrie::new(graph.prefixes());
+    let buffer = RefCell::new(String::new());
+    let context = Context {
+        prefixes: graph.prefixes(),
+        trie: &trie,
+        base: graph.base_ns().as_ref(),
+        config,
+        buffer: &buffer,
+    };
+    write_default_ns(writer, &context)?;
+    let used_prefixes =
+        if config.only_used_prefixes { Some(used_prefixes(graph, &context)) } else { None };
+    write_prefixes(writer, &context, used_prefixes.as_ref())?;
+    write_triples(writer, graph, 0, &BTreeSet::new(), &context)?;
     Ok(())
 }
-fn write_default_ns<W: Write, G: Graph>(writer: &mut W, graph: &G) -> Result<(), PenyuError> {
-    if let Some(default_ns) = graph.base_ns() {
-        writeln!(writer, "BASE <{}> .", default_ns)?;
+
+/// Writes TriG: the default graph as plain triples, and each named graph as a block.
+pub(crate) fn write_graphs<W: Write>(writer: &mut W, base: Option<&Iri>,
+                                     prefixes: &BTreeMap<String, Iri>,
+                                     graphs: &[(Option<Entity>, MemoryGraph)],
+                                     config: &TurtleWriterConfig) -> Result<(), PenyuError> {
+    let trie = PrefixTrie::new(prefixes);
+    let buffer = RefCell::new(String::new());
+    let context = Context { prefixes, trie: &trie, base, config, buffer: &buffer };
+    write_default_ns(writer, &context)?;
+    let used_prefixes =
+        if config.only_used_prefixes {
+            let mut used_prefixes: BTreeSet<&str> = BTreeSet::new();
+            for (graph_name, graph) in graphs {
+                if let Some(Entity::Iri(iri)) = graph_name {
+                    used_prefixes.extend(context.prefix_key(iri));
+                }
+                used_prefixes.extend(self::used_prefixes(graph, &context));
+            }
+            Some(used_prefixes)
+        } else {
+            None
+        };
+    write_prefixes(writer, &context, used_prefixes.as_ref())?;
+    let labeled = shared_blank_nodes(graphs);
+    for (graph_name, graph) in graphs {
+        match graph_name {
+            None => { write_triples(writer, graph, 0, &labeled, &context)?; }
+            Some(graph_name) => {
+                write_entity(writer, graph_name, &context)?;
+                writeln!(writer, " {{")?;
+                write_triples(writer, graph, 1, &labeled, &context)?;
+                writeln!(writer, "}}")?;
+            }
+        }
     }
     Ok(())
 }
 
-fn write_prefixes<W: Write, G: Graph>(writer: &mut W, graph: &G) -> Result<(), PenyuError> {
-    for (key, iri) in graph.prefixes() {
-        writeln!(writer, "PREFIX {}: <{}>", key, iri)?;
+/// The blank nodes that are graph names or occur in more than one graph, which need labels
+/// to keep referring to the same node.
+fn shared_blank_nodes(graphs: &[(Option<Entity>, MemoryGraph)]) -> BTreeSet<BlankNode> {
+    let mut graph_counts: BTreeMap<BlankNode, usize> = BTreeMap::new();
+    let mut shared: BTreeSet<BlankNode> = BTreeSet::new();
+    for (graph_name, graph) in graphs {
+        if let Some(Entity::BlankNode(blank_node)) = graph_name {
+            shared.insert(blank_node.clone());
+        }
+        let mut in_graph: BTreeSet<BlankNode> = BTreeSet::new();
+        for triple in graph.triples() {
+            if let Entity::BlankNode(blank_node) = triple.subject {
+                in_graph.insert(blank_node);
+            }
+            if let Node::Entity(Entity::BlankNode(blank_node)) = triple.object {
+                in_graph.insert(blank_node);
+            }
+        }
+        for blank_node in in_graph {
+            *graph_counts.entry(blank_node).or_default() += 1;
+        }
     }
-    Ok(())
+    shared.extend(graph_counts.into_iter().filter(|(_, n_graphs)| *n_graphs > 1)
+        .map(|(blank_node, _)| blank_node));
+    shared
 }
 
-fn write_triples<W: Write, G: Graph>(writer: &mut W, graph: &G) -> Result<(), PenyuError> {
-    let mut triples_iter = graph.triples();
-    if let Some(triple1) = triples_iter.next() {
-        write_entity(writer, &triple1.subject, graph.prefixes())?;
-        write!(writer, " ")?;
-        write_iri(writer, &triple1.predicate, graph.prefixes())?;
-        write!(writer, " ")?;
-        write_node(writer, &triple1.object, graph.prefixes())?;
-        let mut last_triple = triple1;
-        for triple in triples_iter {
-            if triple.subject == last_triple.subject {
-                if triple.predicate == last_triple.predicate {
-                    write!(writer, ", ")?;
-                    write_node(writer, &triple.object, graph.prefixes())?;
-                } else {
-                    write!(writer, ";\n    ")?;
-                    write_iri(writer, &triple.predicate, graph.prefixes())?;
-                    write!(writer, " ")?;
-                    write_node(writer, &triple.object, graph.prefixes())?;
+/// Writes triples as they come, without needing a `Graph`. Consecutive triples with the same
+/// subject, or subject and predicate, are grouped, so sorted input gives the most compact output.
+/// Blank nodes are never inlined, and `only_used_prefixes` is ignored, since all prefixes are
+/// declared before the first triple. Call `finish` to terminate the last statement.
+pub struct TurtleSerializer<W: Write> {
+    writer: W,
+    prefixes: BTreeMap<String, Iri>,
+    trie: PrefixTrie,
+    base: Option<Iri>,
+    config: TurtleWriterConfig,
+    last: Option<(Entity, Iri)>,
+    buffer: RefCell<String>,
+}
+
+impl<W: Write> TurtleSerializer<W> {
+    pub fn new(mut writer: W, base: Option<Iri>, prefixes: BTreeMap<String, Iri>,
+               config: TurtleWriterConfig) -> Result<TurtleSerializer<W>, PenyuError> {
+        let trie = PrefixTrie::new(&prefixes);
+        let buffer = RefCell::new(String::new());
+        let context = Context {
+            prefixes: &prefixes,
+            trie: &trie,
+            base: base.as_ref(),
+            config: &config,
+            buffer: &buffer,
+        };
+        write_default_ns(&mut writer, &context)?;
+        write_prefixes(&mut writer, &context, None)?;
+        Ok(TurtleSerializer { writer, prefixes, trie, base, config, last: None, buffer })
+    }
+    pub fn serialize(&mut self, triple: &Triple) -> Result<(), PenyuError> {
+        let context = Context {
+            prefixes: &self.prefixes,
+            trie: &self.trie,
+            base: self.base.as_ref(),
+            config: &self.config,
+            buffer: &self.buffer,
+        };
+        let writer = &mut self.writer;
+        match &self.last {
+            Some((subject, predicate))
+            if *subject == triple.subject && *predicate == triple.predicate => {
+                write!(writer, ", ")?;
+            }
+            Some((subject, _)) if *subject == triple.subject => {
+                write!(writer, ";\n{}", context.indent(1))?;
+                write_predicate(writer, &triple.predicate, &context)?;
+                write!(writer, " ")?;
+            }
+            last => {
+                if last.is_some() {
+                    writeln!(writer, " .")?;
                 }
-            } else {
-                writeln!(writer, " .")?;
-                write_entity(writer, &triple.subject, graph.prefixes())?;
+                write_entity(writer, &triple.subject, &context)?;
                 write!(writer, " ")?;
-                write_iri(writer, &triple.predicate, graph.prefixes())?;
+                write_predicate(writer, &triple.predicate, &context)?;
                 write!(writer, " ")?;
-                write_node(writer, &triple.object, graph.prefixes())?;
             }
-            last_triple = triple;
         }
+        write_node(writer, &triple.object, &context)?;
+        self.last = Some((triple.subject.clone(), triple.predicate.clone()));
+        Ok(())
+    }
+    pub fn finish(mut self) -> Result<W, PenyuError> {
+        if self.last.is_some() {
+            writeln!(self.writer, " .")?;
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+fn write_default_ns<W: Write>(writer: &mut W, context: &Context) -> Result<(), PenyuError> {
+    if let Some(default_ns) = context.base {
+        let default_ns = syntax::escape_iri(&default_ns.to_string());
+        match context.config.prefix_syntax {
+            PrefixSyntax::Sparql => { writeln!(writer, "BASE <{}>", default_ns)?; }
+            PrefixSyntax::Turtle => { writeln!(writer, "@base <{}> .", default_ns)?; }
+        }
+    }
+    Ok(())
+}
+
+fn write_prefixes<W: Write>(writer: &mut W, context: &Context,
+                            used_prefixes: Option<&BTreeSet<&str>>) -> Result<(), PenyuError> {
+    for (key, iri) in context.prefixes {
+        let is_unused =
+            used_prefixes.is_some_and(|used_prefixes| !used_prefixes.contains(key.as_str()));
+        if is_unused {
+            continue;
+        }
+        let iri = syntax::escape_iri(&iri.to_string());
+        match context.config.prefix_syntax {
+            PrefixSyntax::Sparql => { writeln!(writer, "PREFIX {}: <{}>", key, iri)?; }
+            PrefixSyntax::Turtle => { writeln!(writer, "@prefix {}: <{}> .", key, iri)?; }
+        }
+    }
+    Ok(())
+}
+
+fn used_prefixes<'a, G: Graph>(graph: &G, context: &'a Context) -> BTreeSet<&'a str> {
+    let mut used_prefixes: BTreeSet<&str> = BTreeSet::new();
+    let mut add_if_used = |iri: &Iri| {
+        if let Some(key) = context.prefix_key(iri) {
+            used_prefixes.insert(key);
+        }
+    };
+    for triple in graph.triples() {
+        if let Entity::Iri(iri) = &triple.subject {
+            add_if_used(iri);
+        }
+        if !(context.config.use_a_for_type && triple.predicate == *vocabs::rdf::TYPE) {
+            add_if_used(&triple.predicate);
+        }
+        match &triple.object {
+            Node::Entity(Entity::Iri(iri)) => { add_if_used(iri); }
+            Node::Entity(Entity::BlankNode(_)) => {}
+            Node::Literal(literal) => {
+                if let LiteralTag::Type(type_iri) = &literal.literal_tag {
+                    if type_iri != vocabs::xsd::STRING
+                        && !(context.config.abbreviate_literals && is_abbreviable(literal)) {
+                        add_if_used(type_iri);
+                    }
+                }
+            }
+        }
+    }
+    used_prefixes
+}
+
+fn write_triples<W: Write, G: Graph>(writer: &mut W, graph: &G, depth: usize,
+                                     labeled: &BTreeSet<BlankNode>, context: &Context)
+                                     -> Result<(), PenyuError> {
+    let subjects: BTreeSet<Entity> = graph.triples().map(|triple| triple.subject).collect();
+    let layout = Layout::with_labeled(graph, &subjects, labeled);
+    for subject in &subjects {
+        if layout.is_inlined(subject) {
+            continue;
+        }
+        write!(writer, "{}", context.indent(depth))?;
+        write_entity(writer, subject, context)?;
+        write!(writer, " ")?;
+        write_predicate_objects(writer, graph, &layout, subject, depth + 1, context)?;
         writeln!(writer, " .")?;
     }
     Ok(())
 }
 
-fn write_node<W: Write>(writer: &mut W, node: &Node, prefixes: &BTreeMap<String, Iri>)
+fn write_predicate_objects<W: Write, G: Graph>(writer: &mut W, graph: &G, layout: &Layout,
+                                               subject: &Entity, depth: usize, context: &Context)
+                                               -> Result<(), PenyuError> {
+    let mut last_predicate: Option<Iri> = None;
+    for triple in graph.triples_matching(Some(subject), None, None) {
+        match &last_predicate {
+            Some(predicate) if *predicate == triple.predicate => {
+                write!(writer, ", ")?;
+            }
+            _ => {
+                if last_predicate.is_some() {
+                    write!(writer, ";\n{}", context.indent(depth))?;
+                }
+                write_predicate(writer, &triple.predicate, context)?;
+                write!(writer, " ")?;
+            }
+        }
+        write_object(writer, graph, layout, &triple.object, depth, context)?;
+        last_predicate = Some(triple.predicate);
+    }
+    Ok(())
+}
+
+fn write_object<W: Write, G: Graph>(writer: &mut W, graph: &G, layout: &Layout, object: &Node,
+                                    depth: usize, context: &Context) -> Result<(), PenyuError> {
+    match object {
+        Node::Entity(entity) if layout.is_inlined(entity) => {
+            if let Some(items) = layout.list_items(graph, entity) {
+                write!(writer, "(")?;
+                for item in &items {
+                    write!(writer, " ")?;
+                    write_object(writer, graph, layout, item, depth, context)?;
+                }
+                write!(writer, " )")?;
+            } else if graph.triples_matching(Some(entity), None, None).next().is_none() {
+                write!(writer, "[]")?;
+            } else {
+                write!(writer, "[\n{}", context.indent(depth + 1))?;
+                write_predicate_objects(writer, graph, layout, entity, depth + 1, context)?;
+                write!(writer, "\n{}]", context.indent(depth))?;
+            }
+            Ok(())
+        }
+        _ => { write_node(writer, object, context) }
+    }
+}
+
+fn write_predicate<W: Write>(writer: &mut W, predicate: &Iri, context: &Context)
+                             -> Result<(), PenyuError> {
+    if context.config.use_a_for_type && predicate == vocabs::rdf::TYPE {
+        write!(writer, "a")?;
+        Ok(())
+    } else {
+        write_iri(writer, predicate, context)
+    }
+}
+
+fn write_node<W: Write>(writer: &mut W, node: &Node, context: &Context)
                         -> Result<(), PenyuError> {
     match node {
-        Node::Entity(entity) => { write_entity(writer, entity, prefixes) }
-        Node::Literal(literal) => { write_literal(writer, literal, prefixes) }
+        Node::Entity(entity) => { write_entity(writer, entity, context) }
+        Node::Literal(literal) => { write_literal(writer, literal, context) }
     }
 }
-fn write_entity<W: Write>(writer: &mut W, entity: &Entity,
-                          prefixes: &BTreeMap<String, Iri>) -> Result<(), PenyuError> {
+fn write_entity<W: Write>(writer: &mut W, entity: &Entity, context: &Context)
+                          -> Result<(), PenyuError> {
     match entity {
-        Entity::Iri(iri) => { write_iri(writer, iri, prefixes) }
+        Entity::Iri(iri) => { write_iri(writer, iri, context) }
         Entity::BlankNode(blank_node) => { write_blank_node(writer, blank_node) }
     }
 }
 
-fn write_iri<W: Write>(writer: &mut W, iri: &Iri, prefixes: &BTreeMap<String, Iri>)
-                       -> Result<(), PenyuError> {
-    let key_local =
-        prefixes.iter().find_map(|(key, prefix_iri)| {
-            iri.iri.strip_prefix(&prefix_iri.iri).map(|local| (key, local))
-        });
-    match key_local {
-        Some((key, local)) if syntax::is_valid_local(&local) => {
-            write!(writer, "{}:", key)?;
-            for c in local {
-                write!(writer, "{}", c)?;
-            }
+fn write_iri<W: Write>(writer: &mut W, iri: &Iri, context: &Context) -> Result<(), PenyuError> {
+    let rendered = context.render(iri);
+    match context.trie.compact(&rendered) {
+        Some((key, local)) => {
+            write!(writer, "{}:{}", key, local)?;
+        }
+        None => {
+            let relative =
+                context.base.filter(|_| context.config.relative_iris)
+                    .and_then(|base| base.relativize(iri));
+            let iri = relative.as_deref().unwrap_or(&rendered);
+            write!(writer, "<{}>", syntax::escape_iri(iri))?
         }
-        _ => { write!(writer, "<{}>", iri)? }
     }
     Ok(())
 }
@@ -100,20 +405,263 @@ fn write_blank_node<W: Write>(writer: &mut W, blank_node: &BlankNode) -> Result<
     Ok(())
 }
 
-fn write_literal<W: Write>(writer: &mut W, literal: &Literal, prefixes: &BTreeMap<String, Iri>)
-                           -> Result<(), PenyuError> {
+fn is_abbreviable(literal: &Literal) -> bool {
     match &literal.literal_tag {
         LiteralTag::Type(type_iri) => {
-            if type_iri == vocabs::xsd::STRING {
-                write!(writer, "\"{}\"", literal.string)?
+            if type_iri == vocabs::xsd::INTEGER {
+                syntax::is_integer(&literal.string)
+            } else if type_iri == vocabs::xsd::DECIMAL {
+                syntax::is_decimal(&literal.string)
+            } else if type_iri == vocabs::xsd::DOUBLE {
+                syntax::is_double(&literal.string)
+            } else if type_iri == vocabs::xsd::BOOLEAN {
+                literal.string == "true" || literal.string == "false"
             } else {
-                write!(writer, "\"{}\"^^", literal.string)?;
-                write_iri(writer, type_iri, prefixes)?
+                false
+            }
+        }
+        LiteralTag::LangTag(_) => { false }
+    }
+}
+
+fn write_literal<W: Write>(writer: &mut W, literal: &Literal, context: &Context)
+                           -> Result<(), PenyuError> {
+    if context.config.abbreviate_literals && is_abbreviable(literal) {
+        write!(writer, "{}", literal.string)?;
+        return Ok(());
+    }
+    write_string(writer, &literal.string)?;
+    match &literal.literal_tag {
+        LiteralTag::Type(type_iri) => {
+            if type_iri != vocabs::xsd::STRING {
+                write!(writer, "^^")?;
+                write_iri(writer, type_iri, context)?
             }
         }
         LiteralTag::LangTag(lang_tag) => {
-            write!(writer, "\"{}\"@{}", literal.string, lang_tag)?
+            write!(writer, "@{}", lang_tag)?
         }
     }
     Ok(())
 }
+
+fn write_string<W: Write>(writer: &mut W, string: &str) -> Result<(), PenyuError> {
+    if string.contains('\n') {
+        write!(writer, "\"\"\"{}\"\"\"", syntax::escape_long_string(string))?
+    } else {
+        write!(writer, "\"{}\"", syntax::escape_string(string))?
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::graph::{Graph, MemoryGraph};
+    use crate::model::iri::Iri;
+    use crate::model::literal::{Literal, LiteralTag};
+    use crate::model::node::BlankNode;
+    use crate::model::triple::Triple;
+    use crate::vocabs::{obo, rdf, rdfs, xsd};
+    use crate::write::turtle::{PrefixSyntax, TurtleSerializer, TurtleWriterConfig};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn groups_objects() {
+        let mut graph = MemoryGraph::new();
+        graph.add_prefix(obo::PREFIX.to_string(), obo::NAMESPACE.clone());
+        graph.add_prefix(rdfs::PREFIX.to_string(), rdfs::NAMESPACE.clone());
+        let uberon = obo::ns::UBERON.append("0002107".to_string());
+        graph.add(&uberon, rdfs::LABEL, Literal::from("liver".to_string()));
+        graph.add(&uberon, rdfs::LABEL, Literal::from("jecur".to_string()));
+        graph.add(&uberon, rdfs::COMMENT, Literal::from("An organ.".to_string()));
+        let mut bytes: Vec<u8> = Vec::new();
+        super::write(&mut bytes, &graph).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(),
+                   "PREFIX obo: <http://purl.obolibrary.org/obo/>\n\
+                    PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>\n\
+                    obo:UBERON_0002107 rdfs:comment \"An organ.\";\n    \
+                    rdfs:label \"jecur\", \"liver\" .\n");
+    }
+
+    #[test]
+    fn escapes_round_trip() {
+        let mut graph = MemoryGraph::new();
+        graph.add_prefix(obo::PREFIX.to_string(), obo::NAMESPACE.clone());
+        graph.add_prefix(rdfs::PREFIX.to_string(), rdfs::NAMESPACE.clone());
+        let strings = [
+            "plain", "with \"quotes\"", "back\\slash", "tab\there", "carriage\rreturn",
+            "line\nbreak", "ends with quote\n\"", "triple \"\"\" quotes\n", "\"\"\n\"\"",
+            "control \u{1}\u{7F}", "unicode \u{E9}\u{1F422}", "", "\\\n\\",
+        ];
+        let efo = obo::NAMESPACE.append("EFO_0000001".to_string());
+        for string in strings {
+            graph.add(&efo, rdfs::COMMENT, Literal::from(string.to_string()));
+        }
+        let iris = [
+            "http://purl.obolibrary.org/obo/ends.", "http://purl.obolibrary.org/obo/-dash",
+            "http://purl.obolibrary.org/obo/a:b",
+        ];
+        for iri in iris {
+            graph.add(&efo, rdfs::SEE_ALSO, Iri::from(iri.to_string()));
+        }
+        let mut bytes: Vec<u8> = Vec::new();
+        super::write(&mut bytes, &graph).unwrap();
+        let graph2 = crate::read::turtle::read(&mut bytes.as_slice()).unwrap();
+        let triples: Vec<Triple> = graph.triples().collect();
+        let triples2: Vec<Triple> = graph2.triples().collect();
+        assert_eq!(triples2.len(), strings.len() + iris.len());
+        assert!(triples == triples2);
+        let invalid_iris = [
+            ("http://example.org/with space", "http://example.org/with%20space"),
+            ("http://example.org/<angle>", "http://example.org/%3Cangle%3E"),
+            ("http://example.org/quote\"", "http://example.org/quote%22"),
+        ];
+        let mut graph = MemoryGraph::new();
+        for (iri, _) in invalid_iris {
+            graph.add(&efo, rdfs::SEE_ALSO, Iri::from(iri.to_string()));
+        }
+        let mut bytes: Vec<u8> = Vec::new();
+        super::write(&mut bytes, &graph).unwrap();
+        let graph2 = crate::read::turtle::read(&mut bytes.as_slice()).unwrap();
+        for (_, encoded) in invalid_iris {
+            let triple = Triple::create(&efo, rdfs::SEE_ALSO, Iri::from(encoded.to_string()));
+            assert!(graph2.contains(&triple), "{}", encoded);
+        }
+    }
+
+    #[test]
+    fn nests_blank_nodes_and_collections() {
+        let owl = Iri::from("http://www.w3.org/2002/07/owl#");
+        let mut graph = MemoryGraph::new();
+        graph.add_prefix(obo::PREFIX.to_string(), obo::NAMESPACE.clone());
+        graph.add_prefix("owl".to_string(), owl.clone());
+        graph.add_prefix(rdf::PREFIX.to_string(), rdf::NAMESPACE.clone());
+        graph.add_prefix(rdfs::PREFIX.to_string(), rdfs::NAMESPACE.clone());
+        let liver = obo::ns::UBERON.append("0002107".to_string());
+        let restriction = BlankNode::from("node0");
+        graph.add(&liver, rdfs::SUB_CLASS_OF, &restriction);
+        graph.add(&restriction, rdf::TYPE, owl.append("Restriction".to_string()));
+        graph.add(&restriction, owl.append("onProperty".to_string()),
+                  obo::ns::RO.append("0002202".to_string()));
+        let union = BlankNode::from("node1");
+        graph.add(&restriction, owl.append("someValuesFrom".to_string()), &union);
+        graph.add(&union, owl.append("unionOf".to_string()), BlankNode::from("node2"));
+        graph.add(BlankNode::from("node2"), rdf::FIRST, obo::ns::UBERON.append("1".to_string()));
+        graph.add(BlankNode::from("node2"), rdf::REST, BlankNode::from("node3"));
+        graph.add(BlankNode::from("node3"), rdf::FIRST, BlankNode::from("node4"));
+        graph.add(BlankNode::from("node3"), rdf::REST, rdf::NIL);
+        graph.add(&liver, rdfs::SEE_ALSO, BlankNode::from("shared"));
+        graph.add(&restriction, rdfs::SEE_ALSO, BlankNode::from("shared"));
+        graph.add(BlankNode::from("cycle0"), rdfs::SEE_ALSO, BlankNode::from("cycle1"));
+        graph.add(BlankNode::from("cycle1"), rdfs::SEE_ALSO, BlankNode::from("cycle0"));
+        let mut bytes: Vec<u8> = Vec::new();
+        super::write(&mut bytes, &graph).unwrap();
+        let turtle = String::from_utf8(bytes).unwrap();
+        assert_eq!(turtle,
+                   "PREFIX obo: <http://purl.obolibrary.org/obo/>\n\
+                    PREFIX owl: <http://www.w3.org/2002/07/owl#>\n\
+                    PREFIX rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#>\n\
+                    PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>\n\
+                    obo:UBERON_0002107 rdfs:seeAlso _:shared;\n    \
+                    rdfs:subClassOf [\n        \
+                    rdf:type owl:Restriction;\n        \
+                    rdfs:seeAlso _:shared;\n        \
+                    owl:onProperty obo:RO_0002202;\n        \
+                    owl:someValuesFrom [\n            \
+                    owl:unionOf ( obo:UBERON_1 [] )\n        \
+                    ]\n    \
+                    ] .\n\
+                    _:cycle0 rdfs:seeAlso [\n        \
+                    rdfs:seeAlso _:cycle0\n    \
+                    ] .\n");
+        let graph2 = crate::read::turtle::read(&mut turtle.as_bytes()).unwrap();
+        assert_eq!(graph2.len(), graph.len());
+    }
+
+    #[test]
+    fn applies_config() {
+        let mut graph = MemoryGraph::new();
+        graph.set_base_ns(Iri::from("http://example.org/"));
+        graph.add_prefix(obo::PREFIX.to_string(), obo::NAMESPACE.clone());
+        graph.add_prefix(rdf::PREFIX.to_string(), rdf::NAMESPACE.clone());
+        graph.add_prefix(rdfs::PREFIX.to_string(), rdfs::NAMESPACE.clone());
+        graph.add_prefix(xsd::PREFIX.to_string(), xsd::NAMESPACE.clone());
+        let liver = obo::ns::UBERON.append("0002107".to_string());
+        graph.add(&liver, rdf::TYPE, Iri::from("http://example.org/Organ"));
+        graph.add(&liver, Iri::from("http://example.org/weight"),
+                  Literal::new("1.5".to_string(), LiteralTag::Type(xsd::DECIMAL.clone())));
+        graph.add(&liver, Iri::from("http://example.org/weight"),
+                  Literal::new("1.".to_string(), LiteralTag::Type(xsd::DECIMAL.clone())));
+        graph.add(&liver, Iri::from("http://example.org/vital"),
+                  Literal::new("true".to_string(), LiteralTag::Type(xsd::BOOLEAN.clone())));
+        let config = TurtleWriterConfig {
+            prefix_syntax: PrefixSyntax::Turtle,
+            use_a_for_type: true,
+            only_used_prefixes: true,
+            abbreviate_literals: true,
+            indent: 2,
+            relative_iris: true,
+        };
+        let mut bytes: Vec<u8> = Vec::new();
+        super::write_with_config(&mut bytes, &graph, &config).unwrap();
+        let turtle = String::from_utf8(bytes).unwrap();
+        assert_eq!(turtle,
+                   "@base <http://example.org/> .\n\
+                    @prefix obo: <http://purl.obolibrary.org/obo/> .\n\
+                    @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .\n\
+                    obo:UBERON_0002107 <vital> true;\n  \
+                    <weight> \"1.\"^^xsd:decimal, 1.5;\n  \
+                    a <Organ> .\n");
+        let graph2 = crate::read::turtle::read(&mut turtle.as_bytes()).unwrap();
+        assert_eq!(graph2.len(), graph.len());
+    }
+
+    #[test]
+    fn uses_longest_prefix() {
+        let mut graph = MemoryGraph::new();
+        graph.add_prefix(obo::PREFIX.to_string(), obo::NAMESPACE.clone());
+        graph.add_prefix(obo::prefixes::MONDO.to_string(), obo::ns::MONDO.clone());
+        graph.add_prefix(rdfs::PREFIX.to_string(), rdfs::NAMESPACE.clone());
+        let diabetes = obo::Ontology::MONDO.create_iri(5148);
+        graph.add(&diabetes, rdfs::SUB_CLASS_OF, obo::NAMESPACE.append("MONDO_/x".to_string()));
+        graph.add(&diabetes, rdfs::SUB_CLASS_OF, obo::ns::SO.append("0000704".to_string()));
+        let mut bytes: Vec<u8> = Vec::new();
+        super::write(&mut bytes, &graph).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(),
+                   "PREFIX mondo: <http://purl.obolibrary.org/obo/MONDO_>\n\
+                    PREFIX obo: <http://purl.obolibrary.org/obo/>\n\
+                    PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>\n\
+                    mondo:0005148 rdfs:subClassOf <http://purl.obolibrary.org/obo/MONDO_/x>, \
+                    obo:SO_0000704 .\n");
+    }
+
+    #[test]
+    fn serializes_stream() {
+        let mut prefixes = BTreeMap::new();
+        prefixes.insert(obo::PREFIX.to_string(), obo::NAMESPACE.clone());
+        prefixes.insert(rdfs::PREFIX.to_string(), rdfs::NAMESPACE.clone());
+        let config = TurtleWriterConfig { use_a_for_type: true, ..Default::default() };
+        let mut serializer =
+            TurtleSerializer::new(Vec::new(), None, prefixes, config).unwrap();
+        let liver = obo::ns::UBERON.append("0002107".to_string());
+        let organ = obo::ns::UBERON.append("0000062".to_string());
+        let triples = [
+            Triple::create(&liver, rdf::TYPE, obo::NAMESPACE.append("Class".to_string())),
+            Triple::create(&liver, rdfs::LABEL, Literal::from("liver".to_string())),
+            Triple::create(&liver, rdfs::LABEL, Literal::from("jecur".to_string())),
+            Triple::create(&organ, rdfs::LABEL, Literal::from("organ".to_string())),
+            Triple::create(&liver, rdfs::SUB_CLASS_OF, &organ),
+        ];
+        for triple in &triples {
+            serializer.serialize(triple).unwrap();
+        }
+        let bytes = serializer.finish().unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(),
+                   "PREFIX obo: <http://purl.obolibrary.org/obo/>\n\
+                    PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>\n\
+                    obo:UBERON_0002107 a obo:Class;\n    \
+                    rdfs:label \"liver\", \"jecur\" .\n\
+                    obo:UBERON_0000062 rdfs:label \"organ\" .\n\
+                    obo:UBERON_0002107 rdfs:subClassOf obo:UBERON_0000062 .\n");
+    }
+}
\ No newline at end of file
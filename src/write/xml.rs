@@ -0,0 +1,406 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use crate::error::PenyuError;
+use crate::model::graph::Graph;
+use crate::model::iri::Iri;
+use crate::model::literal::{Literal, LiteralTag};
+use crate::model::node::{BlankNode, Entity, Node};
+use crate::model::triple::Triple;
+use crate::syntax;
+use crate::vocabs::{rdf, xsd};
+use crate::write::layout::Layout;
+use crate::write::prefix_trie::PrefixTrie;
+
+const INDENT: &str = "  ";
+
+struct Context<'a> {
+    base: Option<&'a Iri>,
+    trie: PrefixTrie,
+    rdf: String,
+    node_ids: BTreeMap<BlankNode, String>,
+}
+
+impl Context<'_> {
+    fn iri_attribute(&self, iri: &Iri) -> String {
+        let iri =
+            self.base.and_then(|base| base.relativize(iri)).unwrap_or_else(|| iri.to_string());
+        syntax::escape_xml_attribute(&iri)
+    }
+    fn node_id<'a>(&'a self, blank_node: &'a BlankNode) -> &'a str {
+        self.node_ids.get(blank_node).map(String::as_str).unwrap_or(blank_node.id())
+    }
+}
+
+/// Writes the graph as RDF/XML. The graph's prefixes become XML namespaces, and predicates
+/// not covered by any of them get generated namespaces.
+pub fn write<W: Write, G: Graph>(writer: &mut W, graph: &G) -> Result<(), PenyuError> {
+    let mut namespaces: BTreeMap<String, Iri> =
+        graph.prefixes().iter().filter(|(key, _)| is_usable_key(key))
+            .map(|(key, namespace)| (key.clone(), namespace.clone())).collect();
+    let rdf_key = rdf_key(&mut namespaces);
+    add_predicate_namespaces(graph, &mut namespaces)?;
+    let context = Context {
+        base: graph.base_ns().as_ref(),
+        trie: PrefixTrie::new(&namespaces),
+        rdf: rdf_key,
+        node_ids: node_ids(graph),
+    };
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    write!(writer, "<{}:RDF", context.rdf)?;
+    for (key, namespace) in &namespaces {
+        let namespace = syntax::escape_xml_attribute(&namespace.to_string());
+        if key.is_empty() {
+            write!(writer, "\n{}xmlns=\"{}\"", INDENT, namespace)?;
+        } else {
+            write!(writer, "\n{}xmlns:{}=\"{}\"", INDENT, key, namespace)?;
+        }
+    }
+    if let Some(base) = context.base {
+        let base = syntax::escape_xml_attribute(&base.to_string());
+        write!(writer, "\n{}xml:base=\"{}\"", INDENT, base)?;
+    }
+    writeln!(writer, ">")?;
+    let subjects: BTreeSet<Entity> = graph.triples().map(|triple| triple.subject).collect();
+    let layout = Layout::new(graph, &subjects);
+    for subject in &subjects {
+        if !layout.is_inlined(subject) {
+            write_node_element(writer, graph, &layout, subject, 1, &context)?;
+        }
+    }
+    writeln!(writer, "</{}:RDF>", context.rdf)?;
+    Ok(())
+}
+
+fn is_usable_key(key: &str) -> bool {
+    key.is_empty() || (syntax::is_nc_name(key) && !key.to_ascii_lowercase().starts_with("xml"))
+}
+
+/// The prefix for the RDF syntax terms, adding one if needed. Attributes like `rdf:about`
+/// cannot use the default namespace.
+fn rdf_key(namespaces: &mut BTreeMap<String, Iri>) -> String {
+    let existing =
+        namespaces.iter().find(|(key, namespace)| !key.is_empty() && *namespace == rdf::NAMESPACE)
+            .map(|(key, _)| key.clone());
+    existing.unwrap_or_else(|| {
+        let key =
+            [rdf::PREFIX.to_string()].into_iter()
+                .chain((1..).map(|i| format!("{}{}", rdf::PREFIX, i)))
+                .find(|key| !namespaces.contains_key(key)).unwrap();
+        namespaces.insert(key.clone(), rdf::NAMESPACE.clone());
+        key
+    })
+}
+
+/// Every predicate needs to be written as a QName, so predicates no prefix covers get a
+/// namespace of their own, split off before the longest suffix that is a valid XML name.
+fn add_predicate_namespaces<G: Graph>(graph: &G, namespaces: &mut BTreeMap<String, Iri>)
+                                      -> Result<(), PenyuError> {
+    let predicates: BTreeSet<Iri> = graph.triples().map(|triple| triple.predicate).collect();
+    let mut trie = PrefixTrie::new(namespaces);
+    for predicate in &predicates {
+        if is_syntax_term(predicate) {
+            return Err(PenyuError::from(format!(
+                "Cannot write predicate <{}> as RDF/XML, since it is an RDF/XML syntax term",
+                predicate
+            )));
+        }
+        if qualified_name(predicate, &trie).is_some() {
+            continue;
+        }
+        let iri = predicate.to_string();
+        let split =
+            iri.char_indices().map(|(i, _)| i).filter(|i| *i > 0)
+                .find(|i| syntax::is_nc_name(&iri[*i..]))
+                .ok_or_else(|| {
+                    PenyuError::from(format!(
+                        "Cannot write predicate <{}> as RDF/XML, since it does not end with \
+                        a valid XML name", iri
+                    ))
+                })?;
+        let key =
+            (1..).map(|i| format!("ns{}", i)).find(|key| !namespaces.contains_key(key)).unwrap();
+        namespaces.insert(key, Iri::from(iri[..split].to_string()));
+        trie = PrefixTrie::new(namespaces);
+    }
+    Ok(())
+}
+
+fn is_syntax_term(iri: &Iri) -> bool {
+    iri.to_string().strip_prefix(&rdf::NAMESPACE.to_string()).is_some_and(|local| {
+        matches!(local, "RDF" | "Description" | "ID" | "about" | "parseType" | "resource"
+            | "nodeID" | "datatype" | "li" | "aboutEach" | "aboutEachPrefix" | "bagID")
+    })
+}
+
+fn qualified_name(iri: &Iri, trie: &PrefixTrie) -> Option<String> {
+    let iri = iri.to_string();
+    let name =
+        trie.longest_match(&iri, syntax::is_nc_name).map(|(key, local)| {
+            if key.is_empty() { local.to_string() } else { format!("{}:{}", key, local) }
+        });
+    name
+}
+
+/// Blank node labels used as `rdf:nodeID`, replacing those that are not valid XML names.
+fn node_ids<G: Graph>(graph: &G) -> BTreeMap<BlankNode, String> {
+    let mut blank_nodes: BTreeSet<BlankNode> = BTreeSet::new();
+    for triple in graph.triples() {
+        if let Entity::BlankNode(blank_node) = triple.subject {
+            blank_nodes.insert(blank_node);
+        }
+        if let Node::Entity(Entity::BlankNode(blank_node)) = triple.object {
+            blank_nodes.insert(blank_node);
+        }
+    }
+    let used: BTreeSet<&str> =
+        blank_nodes.iter().map(BlankNode::id).filter(|id| syntax::is_nc_name(id)).collect();
+    let mut n_generated: usize = 0;
+    let mut node_ids: BTreeMap<BlankNode, String> = BTreeMap::new();
+    for blank_node in &blank_nodes {
+        if syntax::is_nc_name(blank_node.id()) {
+            continue;
+        }
+        let id = loop {
+            let id = format!("genid{}", n_generated);
+            n_generated += 1;
+            if !used.contains(id.as_str()) {
+                break id;
+            }
+        };
+        node_ids.insert(blank_node.clone(), id);
+    }
+    node_ids
+}
+
+fn type_name(triple: &Triple, context: &Context) -> Option<String> {
+    match &triple.object {
+        Node::Entity(Entity::Iri(iri))
+        if triple.predicate == *rdf::TYPE && !is_syntax_term(iri) => {
+            qualified_name(iri, &context.trie)
+        }
+        _ => { None }
+    }
+}
+
+fn write_node_element<W: Write, G: Graph>(writer: &mut W, graph: &G, layout: &Layout,
+                                          subject: &Entity, depth: usize, context: &Context)
+                                          -> Result<(), PenyuError> {
+    let indent = INDENT.repeat(depth);
+    let triples: Vec<Triple> = graph.triples_matching(Some(subject), None, None).collect();
+    let typed = triples.iter().enumerate().find_map(|(i, triple)| {
+        type_name(triple, context).map(|name| (i, name))
+    });
+    let name =
+        match &typed {
+            Some((_, name)) => { name.clone() }
+            None => { format!("{}:Description", context.rdf) }
+        };
+    write!(writer, "{}<{}", indent, name)?;
+    match subject {
+        Entity::Iri(iri) => {
+            write!(writer, " {}:about=\"{}\"", context.rdf, context.iri_attribute(iri))?;
+        }
+        Entity::BlankNode(blank_node) => {
+            if !layout.is_inlined(subject) {
+                write!(writer, " {}:nodeID=\"{}\"", context.rdf, context.node_id(blank_node))?;
+            }
+        }
+    }
+    let i_type = typed.map(|(i, _)| i);
+    let properties: Vec<&Triple> =
+        triples.iter().enumerate().filter(|(i, _)| Some(*i) != i_type)
+            .map(|(_, triple)| triple).collect();
+    if properties.is_empty() {
+        writeln!(writer, "/>")?;
+        return Ok(());
+    }
+    writeln!(writer, ">")?;
+    for triple in properties {
+        write_property_element(writer, graph, layout, &triple.predicate, &triple.object,
+                               depth + 1, context)?;
+    }
+    writeln!(writer, "{}</{}>", indent, name)?;
+    Ok(())
+}
+
+fn write_property_element<W: Write, G: Graph>(writer: &mut W, graph: &G, layout: &Layout,
+                                              predicate: &Iri, object: &Node, depth: usize,
+                                              context: &Context) -> Result<(), PenyuError> {
+    let indent = INDENT.repeat(depth);
+    let name =
+        qualified_name(predicate, &context.trie).ok_or_else(|| {
+            PenyuError::from(format!("No XML name for predicate <{}>", predicate))
+        })?;
+    write!(writer, "{}<{}", indent, name)?;
+    match object {
+        Node::Literal(literal) => {
+            write_literal(writer, literal, context)?;
+            writeln!(writer, "</{}>", name)?;
+        }
+        Node::Entity(entity) if layout.is_inlined(entity) => {
+            let items =
+                layout.list_items(graph, entity)
+                    .filter(|items| items.iter().all(|item| matches!(item, Node::Entity(_))));
+            if let Some(items) = items {
+                writeln!(writer, " {}:parseType=\"Collection\">", context.rdf)?;
+                for item in &items {
+                    if let Node::Entity(item) = item {
+                        write_item(writer, graph, layout, item, depth + 1, context)?;
+                    }
+                }
+                writeln!(writer, "{}</{}>", indent, name)?;
+            } else if graph.triples_matching(Some(entity), None, None).next().is_none() {
+                writeln!(writer, " {}:parseType=\"Resource\"/>", context.rdf)?;
+            } else {
+                writeln!(writer, ">")?;
+                write_node_element(writer, graph, layout, entity, depth + 1, context)?;
+                writeln!(writer, "{}</{}>", indent, name)?;
+            }
+        }
+        Node::Entity(Entity::Iri(iri)) => {
+            writeln!(writer, " {}:resource=\"{}\"/>", context.rdf, context.iri_attribute(iri))?;
+        }
+        Node::Entity(Entity::BlankNode(blank_node)) => {
+            writeln!(writer, " {}:nodeID=\"{}\"/>", context.rdf, context.node_id(blank_node))?;
+        }
+    }
+    Ok(())
+}
+
+fn write_item<W: Write, G: Graph>(writer: &mut W, graph: &G, layout: &Layout, item: &Entity,
+                                  depth: usize, context: &Context) -> Result<(), PenyuError> {
+    if layout.is_inlined(item) {
+        return write_node_element(writer, graph, layout, item, depth, context);
+    }
+    write!(writer, "{}<{}:Description", INDENT.repeat(depth), context.rdf)?;
+    match item {
+        Entity::Iri(iri) => {
+            writeln!(writer, " {}:about=\"{}\"/>", context.rdf, context.iri_attribute(iri))?;
+        }
+        Entity::BlankNode(blank_node) => {
+            writeln!(writer, " {}:nodeID=\"{}\"/>", context.rdf, context.node_id(blank_node))?;
+        }
+    }
+    Ok(())
+}
+
+fn write_literal<W: Write>(writer: &mut W, literal: &Literal, context: &Context)
+                           -> Result<(), PenyuError> {
+    if let Some(c) = literal.string.chars().find(|c| !syntax::is_xml_char(*c)) {
+        return Err(PenyuError::from(format!(
+            "Cannot write literal {:?} as RDF/XML, since it contains {:?}", literal.string, c
+        )));
+    }
+    match &literal.literal_tag {
+        LiteralTag::Type(type_iri) => {
+            if type_iri != xsd::STRING {
+                write!(writer, " {}:datatype=\"{}\"", context.rdf,
+                       context.iri_attribute(type_iri))?;
+            }
+        }
+        LiteralTag::LangTag(lang_tag) => {
+            write!(writer, " xml:lang=\"{}\"", syntax::escape_xml_attribute(lang_tag))?;
+        }
+    }
+    write!(writer, ">{}", syntax::escape_xml_text(&literal.string))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::graph::MemoryGraph;
+    use crate::model::iri::Iri;
+    use crate::model::literal::{Literal, LiteralTag};
+    use crate::model::node::{BlankNode, Entity};
+    use crate::vocabs::{obo, rdf, rdfs, xsd};
+
+    fn n_triples(graph: &MemoryGraph) -> String {
+        let mut bytes: Vec<u8> = Vec::new();
+        crate::write::ntriples::write(&mut bytes, graph).unwrap();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut graph = MemoryGraph::new();
+        graph.set_base_ns(Iri::from("http://example.org/data/"));
+        graph.add_prefix(obo::PREFIX.to_string(), obo::NAMESPACE.clone());
+        graph.add_prefix(rdfs::PREFIX.to_string(), rdfs::NAMESPACE.clone());
+        graph.add_prefix("xmlish".to_string(), Iri::from("http://example.org/xmlish#"));
+        let liver = obo::ns::UBERON.append("0002107".to_string());
+        graph.add(&liver, rdf::TYPE, rdfs::CLASS);
+        graph.add(&liver, rdf::TYPE, Iri::from("http://example.org/types/1"));
+        graph.add(&liver, rdfs::LABEL,
+                  Literal::new("liver".to_string(), LiteralTag::LangTag("en".to_string())));
+        graph.add(&liver, rdfs::COMMENT, Literal::from("<a & b>\n\t\"c\"\r".to_string()));
+        graph.add(&liver, Iri::from("http://example.org/data/weight"),
+                  Literal::new("1.5".to_string(), LiteralTag::Type(xsd::DECIMAL.clone())));
+        graph.add(&liver, Iri::from("http://example.org/xmlish#part-of"),
+                  Iri::from("http://example.org/data/body"));
+        graph.add(Iri::from("http://example.org/data/body"), rdfs::SEE_ALSO,
+                  Iri::from("urn:x:body"));
+        let mut bytes: Vec<u8> = Vec::new();
+        super::write(&mut bytes, &graph).unwrap();
+        let xml = String::from_utf8(bytes).unwrap();
+        assert!(xml.contains("<rdfs:Class rdf:about=\"http://purl.obolibrary.org/obo/UBERON"),
+                "{}", xml);
+        assert!(xml.contains("<ns2:part-of rdf:resource=\"body\"/>"), "{}", xml);
+        assert!(!xml.contains("xmlns:xmlish"), "{}", xml);
+        let read = crate::read::xml::read(&mut xml.as_bytes()).unwrap();
+        assert_eq!(n_triples(&read), n_triples(&graph));
+    }
+
+    #[test]
+    fn nests_blank_nodes_and_collections() {
+        let mut graph = MemoryGraph::new();
+        graph.add_prefix("ex".to_string(), Iri::from("http://example.org/"));
+        let ex = |local: &str| Iri::from(format!("http://example.org/{}", local));
+        graph.add(ex("a"), ex("p"), BlankNode::from("b0"));
+        graph.add(BlankNode::from("b0"), rdf::TYPE, ex("Thing"));
+        graph.add(BlankNode::from("b0"), ex("q"), Literal::from("x".to_string()));
+        graph.add(ex("a"), ex("list"), BlankNode::from("l0"));
+        graph.add(BlankNode::from("l0"), rdf::FIRST, ex("b"));
+        graph.add(BlankNode::from("l0"), rdf::REST, BlankNode::from("l1"));
+        graph.add(BlankNode::from("l1"), rdf::FIRST, BlankNode::from("shared"));
+        graph.add(BlankNode::from("l1"), rdf::REST, rdf::NIL);
+        graph.add(ex("a"), ex("r"), BlankNode::from("shared"));
+        graph.add(ex("a"), ex("s"), BlankNode::from("empty"));
+        graph.add(BlankNode::from("shared"), ex("q"), Literal::from("y".to_string()));
+        let mut bytes: Vec<u8> = Vec::new();
+        super::write(&mut bytes, &graph).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<rdf:RDF
+  xmlns:ex=\"http://example.org/\"
+  xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">
+  <rdf:Description rdf:about=\"http://example.org/a\">
+    <ex:list rdf:parseType=\"Collection\">
+      <rdf:Description rdf:about=\"http://example.org/b\"/>
+      <rdf:Description rdf:nodeID=\"shared\"/>
+    </ex:list>
+    <ex:p>
+      <ex:Thing>
+        <ex:q>x</ex:q>
+      </ex:Thing>
+    </ex:p>
+    <ex:r rdf:nodeID=\"shared\"/>
+    <ex:s rdf:parseType=\"Resource\"/>
+  </rdf:Description>
+  <rdf:Description rdf:nodeID=\"shared\">
+    <ex:q>y</ex:q>
+  </rdf:Description>
+</rdf:RDF>
+");
+    }
+
+    #[test]
+    fn rejects_unwritable_predicates() {
+        let mut graph = MemoryGraph::new();
+        let subject = Entity::from(Iri::from("http://example.org/a"));
+        graph.add(&subject, Iri::from("http://example.org/p/123"), Iri::from("urn:x"));
+        assert!(super::write(&mut Vec::new(), &graph).is_err());
+        let mut graph = MemoryGraph::new();
+        graph.add(&subject, rdfs::LABEL, Literal::from("\u{1}".to_string()));
+        assert!(super::write(&mut Vec::new(), &graph).is_err());
+    }
+}